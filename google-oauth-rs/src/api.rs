@@ -1,3 +1,4 @@
 //! Google OAuth REST API implementations.
 pub mod exchange_access_token;
 pub mod request_authorization;
+pub mod revoke_token;
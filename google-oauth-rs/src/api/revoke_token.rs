@@ -0,0 +1,53 @@
+//! Revoke an access token or a refresh token.
+//! See also [reference](https://developers.google.com/identity/protocols/oauth2/web-server#tokenrevoke).
+use serde::Serialize;
+
+use crate::error::Error;
+use crate::result::Result;
+
+/// Request parameters for the revoke token API.
+/// See also [reference](https://developers.google.com/identity/protocols/oauth2/web-server#tokenrevoke).
+#[derive(Serialize)]
+pub struct RevokeTokenRequestParameters {
+    /// An access token or a refresh token to revoke.
+    /// If the token is an access token and it has a corresponding refresh token, the refresh token will also be revoked.
+    #[serde(rename = "token")]
+    pub token: String,
+}
+
+/// Revokes an access token or a refresh token.
+/// See also [reference](https://developers.google.com/identity/protocols/oauth2/web-server#tokenrevoke).
+///
+/// ## Arguments
+/// - `client` - HTTP client.
+/// - `request_parameter` - Request parameters.
+///
+/// ## Returns
+/// Result with nothing when the token has been revoked.
+pub async fn revoke_token(
+    client: &reqwest::Client,
+    request_parameter: RevokeTokenRequestParameters,
+) -> Result<()> {
+    let response = client
+        .post("https://oauth2.googleapis.com/revoke")
+        .form(&request_parameter)
+        .send()
+        .await
+        .map_err(|error| Error::HttpError(error))?;
+
+    let status_code = response.status();
+
+    if status_code.is_success() {
+        Ok(())
+    } else {
+        let response_text = response
+            .text()
+            .await
+            .map_err(|error| Error::ReadResponseFailed(error))?;
+
+        Err(Error::ApiError {
+            status_code: status_code,
+            response: response_text,
+        })
+    }
+}
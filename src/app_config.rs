@@ -0,0 +1,217 @@
+//! Configuration loaded at startup from `config.json` next to `index.html`,
+//! so that one build can be deployed to several environments.
+use std::sync::OnceLock;
+
+use log::LevelFilter;
+use serde::Deserialize;
+
+use crate::{generated::dotenv::BUILD_CONFIG, identity_toolkit};
+
+const CONFIG_PATH: &str = "/config.json";
+
+static APP_CONFIG: OnceLock<AppConfig> = OnceLock::new();
+
+/// A way to sign in that can be turned off per environment.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Provider {
+    Password,
+    EmailLink,
+    Google,
+    Anonymous,
+}
+
+const ALL_PROVIDERS: &[Provider] = &[
+    Provider::Password,
+    Provider::EmailLink,
+    Provider::Google,
+    Provider::Anonymous,
+];
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct AppConfig {
+    pub(crate) firebase_api_key: String,
+    pub(crate) google_client_id: String,
+    /// The origin of the OAuth redirect URI if it differs from the one
+    /// the app is served on.
+    pub(crate) redirect_origin: Option<String>,
+    pub(crate) enabled_providers: Vec<Provider>,
+    pub(crate) log_level: LevelFilter,
+    /// The base URL of the Identity Toolkit API, which is only compiled
+    /// from `.env` and replaced by tests.
+    pub(crate) identity_toolkit_url: String,
+}
+
+/// The content of `config.json`, where every key is optional.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConfigFile {
+    firebase_api_key: Option<String>,
+    // NOTE: No client secret, since this file is public
+    google_client_id: Option<String>,
+    redirect_origin: Option<String>,
+    enabled_providers: Option<Vec<Provider>>,
+    log_level: Option<String>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum AppConfigError {
+    #[error("Failed to fetch config.json: {0:?}")]
+    FetchFailed(reqwest::Error),
+    #[error("Failed to parse config.json: {0}")]
+    InvalidJson(serde_json::Error),
+    #[error("`{key}` must not be empty")]
+    EmptyValue { key: &'static str },
+    #[error("`redirect_origin` is not an origin such as https://example.com: {0}")]
+    InvalidOrigin(String),
+    #[error("`log_level` is unknown: {0}")]
+    InvalidLogLevel(String),
+    #[error("`enabled_providers` must not be empty")]
+    NoProvider,
+}
+
+impl AppConfig {
+    /// The values compiled from `.env`, used when no `config.json` is served.
+    fn compiled() -> Self {
+        Self {
+            firebase_api_key: BUILD_CONFIG
+                .firebase_api_key
+                .to_string(),
+            google_client_id: BUILD_CONFIG
+                .google_client_id
+                .to_string(),
+            redirect_origin: None,
+            enabled_providers: ALL_PROVIDERS.to_vec(),
+            log_level: LevelFilter::Info,
+            identity_toolkit_url: BUILD_CONFIG
+                .identity_toolkit_url
+                .unwrap_or(identity_toolkit::DEFAULT_BASE_URL)
+                .to_string(),
+        }
+    }
+
+    pub(crate) fn is_enabled(
+        &self,
+        provider: Provider,
+    ) -> bool {
+        self.enabled_providers
+            .contains(&provider)
+    }
+
+    fn merge(
+        self,
+        file: ConfigFile,
+    ) -> Result<Self, AppConfigError> {
+        let log_level = match file.log_level {
+            | Some(log_level) => log_level
+                .parse::<LevelFilter>()
+                .map_err(|_| AppConfigError::InvalidLogLevel(log_level))?,
+            | None => self.log_level,
+        };
+
+        Ok(Self {
+            firebase_api_key: file
+                .firebase_api_key
+                .unwrap_or(self.firebase_api_key),
+            google_client_id: file
+                .google_client_id
+                .unwrap_or(self.google_client_id),
+            redirect_origin: file
+                .redirect_origin
+                .or(self.redirect_origin),
+            enabled_providers: file
+                .enabled_providers
+                .unwrap_or(self.enabled_providers),
+            log_level,
+            identity_toolkit_url: self.identity_toolkit_url,
+        })
+    }
+
+    fn validate(self) -> Result<Self, AppConfigError> {
+        if self.firebase_api_key.trim().is_empty() {
+            return Err(AppConfigError::EmptyValue {
+                key: "firebase_api_key",
+            });
+        }
+
+        if self.is_enabled(Provider::Google)
+            && self.google_client_id.trim().is_empty()
+        {
+            return Err(AppConfigError::EmptyValue {
+                key: "google_client_id",
+            });
+        }
+
+        if self.enabled_providers.is_empty() {
+            return Err(AppConfigError::NoProvider);
+        }
+
+        if let Some(origin) = &self.redirect_origin {
+            let is_origin = reqwest::Url::parse(origin)
+                .map(|url| {
+                    matches!(url.scheme(), "http" | "https")
+                        && url.path() == "/"
+                        && url.query().is_none()
+                        && !origin.ends_with('/')
+                })
+                .unwrap_or(false);
+            if !is_origin {
+                return Err(AppConfigError::InvalidOrigin(origin.clone()));
+            }
+        }
+
+        Ok(self)
+    }
+}
+
+/// Fetches `config.json` and layers it over the compiled values.
+pub(crate) async fn load() -> Result<AppConfig, AppConfigError> {
+    let origin = web_sys::window()
+        .and_then(|window| window.location().origin().ok())
+        .unwrap_or_default();
+
+    log::info!("Load app config");
+    let response = reqwest::get(format!("{}{}", origin, CONFIG_PATH))
+        .await
+        .map_err(AppConfigError::FetchFailed)?;
+
+    // NOTE: The dev server answers index.html to a missing file
+    let is_json = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .map(|content_type| content_type.starts_with("application/json"))
+        .unwrap_or(false);
+    if !response.status().is_success() || !is_json {
+        log::info!("No app config is served, use compiled values");
+        return AppConfig::compiled().validate();
+    }
+
+    let text = response
+        .text()
+        .await
+        .map_err(AppConfigError::FetchFailed)?;
+    let file = serde_json::from_str::<ConfigFile>(&text)
+        .map_err(AppConfigError::InvalidJson)?;
+
+    AppConfig::compiled()
+        .merge(file)?
+        .validate()
+}
+
+/// Makes the loaded config available to [`get`].
+pub(crate) fn install(config: AppConfig) {
+    if APP_CONFIG.set(config).is_err() {
+        log::warn!("App config has already been installed");
+    }
+}
+
+/// The loaded config.
+///
+/// Panics before [`install`], since the compiled values may differ from
+/// the ones of `config.json`.
+pub(crate) fn get() -> &'static AppConfig {
+    APP_CONFIG
+        .get()
+        .expect("App config is not installed")
+}
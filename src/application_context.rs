@@ -1,18 +1,72 @@
 use fars::Config;
 use fars::Session;
 
+use crate::auth::StoredAccounts;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum AuthStatus {
+    SignedOut,
+    // NOTE: Sessions of the persisted accounts are being restored
+    Restoring,
+    SigningIn,
+    SignedIn,
+    Refreshing,
+}
+
+/// A signed-in account kept next to the others.
+#[derive(Clone)]
+pub(crate) struct Account {
+    pub(crate) local_id: String,
+    // NOTE: Shown in the account switcher, e.g. the e-mail address
+    pub(crate) name: String,
+    pub(crate) session: Session,
+}
+
 pub(crate) struct ApplicationContext {
     pub(crate) auth_config: Config,
-    pub(crate) auth_session: Option<Session>,
+    pub(crate) accounts: Vec<Account>,
+    // NOTE: Also holds the accounts that could not be restored yet
+    pub(crate) stored_accounts: StoredAccounts,
+    pub(crate) active_account: Option<String>,
+    pub(crate) auth_status: AuthStatus,
+    // NOTE: Incremented on every session change to discard stale results
+    pub(crate) auth_revision: u64,
+    // NOTE: Another account is signing in while keeping the current one
+    pub(crate) adding_account: bool,
 }
 
 impl Default for ApplicationContext {
     fn default() -> Self {
+        let stored_accounts = StoredAccounts::load();
+
         Self {
             auth_config: Config::new(
-                crate::generated::dotenv::FIREBASE_API_KEY.to_string(),
+                crate::app_config::get()
+                    .firebase_api_key
+                    .clone(),
             ),
-            auth_session: None,
+            accounts: Vec::new(),
+            active_account: None,
+            auth_status: match stored_accounts.is_empty() {
+                | false => AuthStatus::Restoring,
+                | true => AuthStatus::SignedOut,
+            },
+            stored_accounts,
+            auth_revision: 0,
+            adding_account: false,
         }
     }
 }
+
+impl ApplicationContext {
+    pub(crate) fn active_session(&self) -> Option<&Session> {
+        self.active_account
+            .as_ref()
+            .and_then(|local_id| {
+                self.accounts
+                    .iter()
+                    .find(|account| &account.local_id == local_id)
+            })
+            .map(|account| &account.session)
+    }
+}
@@ -0,0 +1,142 @@
+use std::{cell::Cell, future::Future, rc::Rc};
+
+use dioxus::prelude::{
+    component, dioxus_elements, fc_to_builder, render, use_state, Element,
+    EventHandler, IntoDynNode, MouseEvent, Props, Scope, ScopeState,
+    UseState,
+};
+use material_dioxus::{MatButton, MatCircularProgress};
+
+use crate::error_presentation::ErrorPresentation;
+
+/// The state of an action triggered by the user.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum ActionState {
+    Idle,
+    Pending,
+    Succeeded,
+    Failed(ErrorPresentation),
+}
+
+/// A handle of an action that runs one request at a time.
+#[derive(Clone, PartialEq)]
+pub(crate) struct UseAsyncAction {
+    state: UseState<ActionState>,
+    mounted: Rc<Cell<bool>>,
+}
+
+// NOTE: Dropped with the hooks when the route is left
+struct UnmountGuard(Rc<Cell<bool>>);
+
+impl Drop for UnmountGuard {
+    fn drop(&mut self) {
+        self.0.set(false);
+    }
+}
+
+pub(crate) fn use_async_action(cx: &ScopeState) -> &UseAsyncAction {
+    // Setup hooks
+    let state = use_state(cx, || ActionState::Idle);
+    let mounted = cx.use_hook(|| Rc::new(Cell::new(true)));
+    cx.use_hook(|| UnmountGuard(mounted.clone()));
+
+    cx.use_hook(|| UseAsyncAction {
+        state: state.clone(),
+        mounted: mounted.clone(),
+    })
+}
+
+impl UseAsyncAction {
+    pub(crate) fn state(&self) -> ActionState {
+        self.state.current().as_ref().clone()
+    }
+
+    pub(crate) fn is_pending(&self) -> bool {
+        *self.state.current() == ActionState::Pending
+    }
+
+    pub(crate) fn error(&self) -> Option<ErrorPresentation> {
+        match self.state() {
+            | ActionState::Failed(error) => Some(error),
+            | _ => None,
+        }
+    }
+
+    pub(crate) fn reset(&self) {
+        if !self.is_pending() {
+            self.state.set(ActionState::Idle);
+        }
+    }
+
+    /// Runs the task unless another one is still pending.
+    ///
+    /// The task is cancelled with the component, and a result that
+    /// arrives after leaving the route is ignored.
+    pub(crate) fn run<F>(
+        &self,
+        cx: &ScopeState,
+        task: F,
+    ) where
+        F: Future<Output = Result<(), ErrorPresentation>> + 'static,
+    {
+        if self.is_pending() {
+            log::warn!("Ignore duplicated action");
+            return;
+        }
+
+        self.state.set(ActionState::Pending);
+
+        let state = self.state.clone();
+        let mounted = self.mounted.clone();
+        cx.spawn(async move {
+            let result = task.await;
+            if !mounted.get() {
+                log::info!("Ignore result of the left route");
+                return;
+            }
+
+            match result {
+                | Ok(()) => state.set(ActionState::Succeeded),
+                | Err(error) => state.set(ActionState::Failed(error)),
+            }
+        });
+    }
+}
+
+/// A button that is disabled and shows progress while the action is
+/// pending.
+#[allow(non_snake_case)]
+#[component(no_case_check)]
+pub(crate) fn ActionButton<'a>(
+    cx: Scope<'a>,
+    #[props(into)] label: String,
+    action: &'a UseAsyncAction,
+    #[props(default)] disabled: bool,
+    onclick: EventHandler<'a, MouseEvent>,
+) -> Element<'a> {
+    let is_pending = action.is_pending();
+
+    render! {
+        span {
+            onclick: move |event| {
+                if !is_pending && !*disabled {
+                    onclick.call(event)
+                }
+            },
+            MatButton {
+                label: label.clone(),
+                outlined: true,
+                disabled: *disabled || is_pending,
+            }
+        }
+
+        if is_pending {
+            render! {
+                MatCircularProgress {
+                    indeterminate: true,
+                    density: -4,
+                }
+            }
+        }
+    }
+}
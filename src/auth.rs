@@ -0,0 +1,606 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use dioxus::prelude::{use_shared_state, ScopeState, UseSharedState};
+use fars::{
+    data::UserData,
+    error::{CommonErrorCode, Error},
+    Config, Session,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    application_context::{Account, ApplicationContext, AuthStatus},
+    identity_toolkit::TokenSession,
+    storage::{self, StorageKind, ACCOUNTS_KEY},
+};
+
+/// A session taken out of the auth store for a single operation.
+///
+/// The revision is compared when the result is written back,
+/// so a sign-out or another sign-in during the request wins.
+#[derive(Clone)]
+pub(crate) struct SessionSnapshot {
+    pub(crate) session: Session,
+    pub(crate) revision: u64,
+}
+
+/// A handle of the auth store.
+///
+/// Reading through this handle subscribes the component to auth
+/// changes, and no lock is held while a request is in flight.
+#[derive(Clone)]
+pub(crate) struct UseAuth {
+    context: UseSharedState<ApplicationContext>,
+}
+
+/// Subscribes the component to the auth store.
+pub(crate) fn use_auth(cx: &ScopeState) -> &UseAuth {
+    let context = use_shared_state::<ApplicationContext>(cx)
+        .expect("ApplicationContext is not provided")
+        .clone();
+
+    cx.use_hook(|| UseAuth {
+        context,
+    })
+}
+
+/// An entry of the account switcher.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct AccountSummary {
+    pub(crate) local_id: String,
+    pub(crate) name: String,
+    pub(crate) is_active: bool,
+}
+
+impl UseAuth {
+    pub(crate) fn config(&self) -> Config {
+        self.context
+            .read()
+            .auth_config
+            .clone()
+    }
+
+    pub(crate) fn status(&self) -> AuthStatus {
+        self.context.read().auth_status
+    }
+
+    pub(crate) fn is_signed_in(&self) -> bool {
+        self.context
+            .read()
+            .active_session()
+            .is_some()
+    }
+
+    pub(crate) fn active_account_id(&self) -> Option<String> {
+        self.context
+            .read()
+            .active_account
+            .clone()
+    }
+
+    pub(crate) fn accounts(&self) -> Vec<AccountSummary> {
+        let context = self.context.read();
+        context
+            .accounts
+            .iter()
+            .map(|account| AccountSummary {
+                local_id: account.local_id.clone(),
+                name: account.name.clone(),
+                is_active: context.active_account.as_ref()
+                    == Some(&account.local_id),
+            })
+            .collect()
+    }
+
+    pub(crate) fn snapshot(&self) -> Option<SessionSnapshot> {
+        let context = self.context.read();
+        context
+            .active_session()
+            .cloned()
+            .map(|session| SessionSnapshot {
+                session,
+                revision: context.auth_revision,
+            })
+    }
+
+    pub(crate) fn begin_sign_in(&self) {
+        self.context.write().auth_status = AuthStatus::SigningIn;
+    }
+
+    /// Adds the signed-in account, or replaces the session of the same
+    /// account, and makes it active.
+    ///
+    /// The refresh token of the same account is kept when none is given.
+    pub(crate) fn complete_sign_in(
+        &self,
+        session: Session,
+        refresh_token: Option<String>,
+    ) {
+        let mut context = self.context.write();
+        let claims = decode_claims(&session.id_token);
+        let local_id = claims
+            .as_ref()
+            .map(|claims| claims.sub.clone())
+            .unwrap_or_else(|| format!("account-{}", context.auth_revision));
+        let name = claims
+            .and_then(|claims| claims.email)
+            .unwrap_or("Guest".to_string());
+
+        context
+            .stored_accounts
+            .upsert(&local_id, &name, refresh_token);
+        let account = Account {
+            local_id: local_id.clone(),
+            name,
+            session,
+        };
+        match context
+            .accounts
+            .iter_mut()
+            .find(|account| account.local_id == local_id)
+        {
+            | Some(existing) => *existing = account,
+            | None => context.accounts.push(account),
+        }
+
+        context.active_account = Some(local_id);
+        context.auth_status = AuthStatus::SignedIn;
+        context.auth_revision += 1;
+        context.adding_account = false;
+        persist_accounts(&mut context);
+    }
+
+    pub(crate) fn fail_sign_in(&self) {
+        let mut context = self.context.write();
+        context.auth_status = match context.active_session() {
+            | Some(_) => AuthStatus::SignedIn,
+            | None => AuthStatus::SignedOut,
+        };
+    }
+
+    /// Lets the sign-in routes add another account without dropping
+    /// the current one.
+    pub(crate) fn begin_add_account(&self) {
+        self.context.write().adding_account = true;
+    }
+
+    pub(crate) fn cancel_add_account(&self) {
+        self.context.write().adding_account = false;
+    }
+
+    pub(crate) fn is_adding_account(&self) -> bool {
+        self.context.read().adding_account
+    }
+
+    pub(crate) fn switch_account(
+        &self,
+        local_id: &str,
+    ) {
+        let mut context = self.context.write();
+        if !context
+            .accounts
+            .iter()
+            .any(|account| account.local_id == local_id)
+        {
+            log::error!("Account is not found: {}", local_id);
+            return;
+        }
+
+        context.active_account = Some(local_id.to_string());
+        context.auth_status = AuthStatus::SignedIn;
+        context.auth_revision += 1;
+        persist_accounts(&mut context);
+    }
+
+    /// Takes a snapshot and marks the store as refreshing until
+    /// the snapshot is written back by
+    /// [`UseAuth::update_session`] or [`UseAuth::end_refresh`].
+    pub(crate) fn begin_refresh(&self) -> Option<SessionSnapshot> {
+        let snapshot = self.snapshot();
+        if snapshot.is_some() {
+            self.context.write().auth_status = AuthStatus::Refreshing;
+        }
+        snapshot
+    }
+
+    pub(crate) fn end_refresh(
+        &self,
+        snapshot: &SessionSnapshot,
+    ) {
+        let mut context = self.context.write();
+        if context.auth_revision == snapshot.revision {
+            context.auth_status = AuthStatus::SignedIn;
+        }
+    }
+
+    /// Writes back a session returned by an API call.
+    ///
+    /// Returns `false` when the store has changed since the
+    /// snapshot was taken and the session was discarded.
+    pub(crate) fn update_session(
+        &self,
+        snapshot: &SessionSnapshot,
+        session: Session,
+    ) -> bool {
+        self.write_back(snapshot, session, None)
+    }
+
+    /// Writes back a session with the refresh token that replaces the
+    /// revoked one, e.g. after enrolling a second factor.
+    pub(crate) fn update_token_session(
+        &self,
+        snapshot: &SessionSnapshot,
+        session: TokenSession,
+    ) -> bool {
+        self.write_back(
+            snapshot,
+            session.session,
+            Some(session.refresh_token),
+        )
+    }
+
+    fn write_back(
+        &self,
+        snapshot: &SessionSnapshot,
+        session: Session,
+        refresh_token: Option<String>,
+    ) -> bool {
+        let mut context = self.context.write();
+        if context.auth_revision != snapshot.revision {
+            log::warn!("Discard stale session");
+            return false;
+        }
+
+        let active_account = context.active_account.clone();
+        if let Some(account) = context
+            .accounts
+            .iter_mut()
+            .find(|account| Some(&account.local_id) == active_account.as_ref())
+        {
+            account.session = session;
+            let (local_id, name) =
+                (account.local_id.clone(), account.name.clone());
+            context
+                .stored_accounts
+                .upsert(&local_id, &name, refresh_token);
+        }
+        context.auth_status = AuthStatus::SignedIn;
+        context.auth_revision += 1;
+        persist_accounts(&mut context);
+        true
+    }
+
+    /// Signs out the active account and switches to the next one if any.
+    pub(crate) fn sign_out(&self) {
+        if let Some(local_id) = self.active_account_id() {
+            self.sign_out_account(&local_id);
+        }
+    }
+
+    /// Signs out the account, and switches to the next one if it is the
+    /// active one.
+    pub(crate) fn sign_out_account(
+        &self,
+        local_id: &str,
+    ) {
+        let mut context = self.context.write();
+        context
+            .stored_accounts
+            .remove(local_id);
+        if !context
+            .accounts
+            .iter()
+            .any(|account| account.local_id == local_id)
+        {
+            persist_accounts(&mut context);
+            return;
+        }
+
+        context
+            .accounts
+            .retain(|account| account.local_id != local_id);
+        if context.active_account.as_deref() == Some(local_id) {
+            context.active_account = context
+                .accounts
+                .first()
+                .map(|account| account.local_id.clone());
+        }
+        context.auth_status = match context.active_account {
+            | Some(_) => AuthStatus::SignedIn,
+            | None => AuthStatus::SignedOut,
+        };
+        context.auth_revision += 1;
+        persist_accounts(&mut context);
+    }
+}
+
+/// The accounts persisted by their refresh tokens, where ID tokens are
+/// issued again on the next visit.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) struct StoredAccounts {
+    active_account: Option<String>,
+    accounts: Vec<StoredAccount>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct StoredAccount {
+    local_id: String,
+    name: String,
+    refresh_token: String,
+}
+
+impl StoredAccounts {
+    pub(crate) fn load() -> Self {
+        storage::load::<StoredAccounts>(StorageKind::Local, ACCOUNTS_KEY)
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+    }
+
+    /// Records the account, keeping the known refresh token when none is
+    /// given.
+    ///
+    /// An account without any known refresh token is not recorded.
+    fn upsert(
+        &mut self,
+        local_id: &str,
+        name: &str,
+        refresh_token: Option<String>,
+    ) {
+        match self
+            .accounts
+            .iter_mut()
+            .find(|account| account.local_id == local_id)
+        {
+            | Some(existing) => {
+                existing.name = name.to_string();
+                if let Some(refresh_token) = refresh_token {
+                    existing.refresh_token = refresh_token;
+                }
+            },
+            | None => {
+                if let Some(refresh_token) = refresh_token {
+                    self.accounts.push(StoredAccount {
+                        local_id: local_id.to_string(),
+                        name: name.to_string(),
+                        refresh_token,
+                    });
+                }
+            },
+        }
+    }
+
+    fn remove(
+        &mut self,
+        local_id: &str,
+    ) {
+        self.accounts
+            .retain(|account| account.local_id != local_id);
+    }
+}
+
+fn persist_accounts(context: &mut ApplicationContext) {
+    context.stored_accounts.active_account =
+        context.active_account.clone();
+    storage::save(
+        StorageKind::Local,
+        ACCOUNTS_KEY,
+        &context.stored_accounts,
+    );
+}
+
+// NOTE: Other errors such as a network error may pass on the next visit
+fn is_rejected_token(error_code: &CommonErrorCode) -> bool {
+    matches!(
+        error_code,
+        CommonErrorCode::TokenExpired
+            | CommonErrorCode::InvalidRefreshToken
+            | CommonErrorCode::UserDisabled
+            | CommonErrorCode::UserNotFound
+    )
+}
+
+/// Restores the sessions of the persisted accounts.
+///
+/// An account is forgotten only when its refresh token is rejected, and
+/// kept for the next visit on other errors.
+pub(crate) async fn restore_accounts(auth: UseAuth) {
+    let stored = auth
+        .context
+        .read()
+        .stored_accounts
+        .clone();
+    if stored.is_empty() {
+        return;
+    }
+
+    let config = auth.config();
+    let mut accounts = Vec::new();
+    let mut rejected = Vec::new();
+    for stored_account in stored.accounts {
+        log::info!("Restore account: {}", stored_account.name);
+        match config
+            .exchange_refresh_token(stored_account.refresh_token)
+            .await
+        {
+            | Ok(session) => accounts.push(Account {
+                local_id: stored_account.local_id,
+                name: stored_account.name,
+                session,
+            }),
+            | Err(Error::ApiError {
+                error_code,
+                ..
+            }) if is_rejected_token(&error_code) => {
+                log::error!(
+                    "Refresh token is rejected, forget account: {:?}",
+                    error_code
+                );
+                rejected.push(stored_account.local_id);
+            },
+            | Err(error) => {
+                log::error!("Restore account failed: {:?}", error);
+            },
+        }
+    }
+
+    let mut context = auth.context.write();
+    let is_changed = !accounts.is_empty() || !rejected.is_empty();
+    for local_id in &rejected {
+        context
+            .stored_accounts
+            .remove(local_id);
+    }
+
+    // NOTE: Accounts signed in during the restoration are kept
+    for account in accounts {
+        if !context
+            .accounts
+            .iter()
+            .any(|existing| existing.local_id == account.local_id)
+        {
+            context.accounts.push(account);
+        }
+    }
+
+    if context.active_session().is_none() {
+        context.active_account = stored
+            .active_account
+            .filter(|local_id| {
+                context
+                    .accounts
+                    .iter()
+                    .any(|account| &account.local_id == local_id)
+            })
+            .or_else(|| {
+                context
+                    .accounts
+                    .first()
+                    .map(|account| account.local_id.clone())
+            });
+    }
+    if context.auth_status == AuthStatus::Restoring {
+        context.auth_status = match context.active_account {
+            | Some(_) => AuthStatus::SignedIn,
+            | None => AuthStatus::SignedOut,
+        };
+    }
+    context.auth_revision += 1;
+    // NOTE: Nothing is written when every account failed temporarily
+    if is_changed {
+        persist_accounts(&mut context);
+    }
+}
+
+#[derive(Deserialize)]
+struct IdTokenClaims {
+    sub: String,
+    email: Option<String>,
+}
+
+/// The local ID of the user that the session belongs to.
+pub(crate) fn local_id_of(session: &Session) -> Option<String> {
+    decode_claims(&session.id_token).map(|claims| claims.sub)
+}
+
+fn decode_claims(id_token: &str) -> Option<IdTokenClaims> {
+    let payload = id_token.split('.').nth(1)?;
+    let payload = URL_SAFE_NO_PAD
+        .decode(payload.trim_end_matches('='))
+        .ok()?;
+
+    serde_json::from_slice(&payload).ok()
+}
+
+/// Returns whether the user signed up with an e-mail and password
+/// and has not verified the address yet.
+pub(crate) fn requires_email_verification(user_data: &UserData) -> bool {
+    let has_password_provider = user_data
+        .provider_user_info
+        .iter()
+        .flatten()
+        .any(|provider| provider.provider_id == "password");
+
+    has_password_provider && !user_data.email_verified.unwrap_or(false)
+}
+
+/// Anonymous users have no linked provider.
+pub(crate) fn is_anonymous(user_data: &UserData) -> bool {
+    user_data
+        .provider_user_info
+        .iter()
+        .flatten()
+        .next()
+        .is_none()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stored(accounts: &[(&str, &str)]) -> StoredAccounts {
+        StoredAccounts {
+            active_account: None,
+            accounts: accounts
+                .iter()
+                .map(|(local_id, refresh_token)| StoredAccount {
+                    local_id: local_id.to_string(),
+                    name: format!("{}@example.com", local_id),
+                    refresh_token: refresh_token.to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn account_without_token_is_not_stored() {
+        let mut accounts = StoredAccounts::default();
+        accounts.upsert("alice", "alice@example.com", None);
+
+        assert!(accounts.is_empty());
+    }
+
+    #[test]
+    fn rotated_token_replaces_persisted_one() {
+        let mut accounts = stored(&[("alice", "token-1"), ("bob", "token-2")]);
+        accounts.upsert(
+            "alice",
+            "alice@example.com",
+            Some("token-3".to_string()),
+        );
+
+        assert_eq!(
+            accounts,
+            stored(&[("alice", "token-3"), ("bob", "token-2")])
+        );
+    }
+
+    #[test]
+    fn known_token_is_kept_without_new_one() {
+        let mut accounts = stored(&[("alice", "token-1")]);
+        accounts.upsert("alice", "new@example.com", None);
+
+        assert_eq!(accounts.accounts[0].refresh_token, "token-1");
+        assert_eq!(accounts.accounts[0].name, "new@example.com");
+    }
+
+    #[test]
+    fn account_is_removed_alone() {
+        let mut accounts = stored(&[("alice", "token-1"), ("bob", "token-2")]);
+        accounts.remove("alice");
+
+        assert_eq!(accounts, stored(&[("bob", "token-2")]));
+    }
+
+    #[test]
+    fn only_rejected_token_forgets_account() {
+        assert!(is_rejected_token(&CommonErrorCode::TokenExpired));
+        assert!(is_rejected_token(&CommonErrorCode::InvalidRefreshToken));
+        assert!(is_rejected_token(&CommonErrorCode::UserDisabled));
+        assert!(is_rejected_token(&CommonErrorCode::UserNotFound));
+        assert!(!is_rejected_token(
+            &CommonErrorCode::TooManyAttemptsTryLater
+        ));
+    }
+}
@@ -1,52 +1,348 @@
-use std::{
-    collections::HashMap,
-    fs::File,
-    io::{self, BufRead, Write},
-    path::Path,
-};
+// NOTE: Shared with the crate to be tested there
+#[path = "dotenv_parser.rs"]
+mod dotenv_parser;
+
+use std::{collections::BTreeMap, fmt::Write as _, path::Path};
+
+use anyhow::{bail, Context};
+
+use crate::dotenv_parser::parse_env;
+
+const ENV_FILE: &str = "./.env";
+const GENERATED_PATH: &str = "./src/generated/dotenv.rs";
+
+/// The variable that selects the profile, e.g. `APP_PROFILE=staging`.
+const PROFILE_VAR: &str = "APP_PROFILE";
+const DEFAULT_PROFILE: &str = "development";
+/// The profiles that can also be selected by a cargo feature.
+const PROFILE_FEATURES: &[&str] = &[
+    "staging",
+    "production",
+];
+
+/// The type a value is checked against.
+#[derive(Clone, Copy)]
+enum Kind {
+    NonEmpty,
+    Url,
+    Bool,
+}
+
+struct Key {
+    name: &'static str,
+    kind: Kind,
+    required: bool,
+}
+
+/// The keys read from `.env` or the process environment.
+const SCHEMA: &[Key] = &[
+    Key {
+        name: "FIREBASE_API_KEY",
+        kind: Kind::NonEmpty,
+        required: true,
+    },
+    Key {
+        name: "GOOGLE_CLIENT_ID",
+        kind: Kind::NonEmpty,
+        required: true,
+    },
+    Key {
+        name: "GOOGLE_CLIENT_SECRET",
+        kind: Kind::NonEmpty,
+        required: true,
+    },
+    Key {
+        name: "GOOGLE_REDIRECT_URI",
+        kind: Kind::Url,
+        required: false,
+    },
+    Key {
+        name: "IDENTITY_TOOLKIT_URL",
+        kind: Kind::Url,
+        required: false,
+    },
+    Key {
+        name: "SHOW_PROFILE_BANNER",
+        kind: Kind::Bool,
+        required: false,
+    },
+];
+
+/// A value that passed the check of its [`Kind`].
+enum Value {
+    Str(String),
+    Bool(bool),
+}
 
 fn main() -> anyhow::Result<()> {
-    // Setup target directory and file
-    let generated_path = Path::new("./src/generated/dotenv.rs");
-    std::fs::create_dir_all("./src/generated")?;
-    let mut file = std::fs::File::create(generated_path)?;
-
-    // Load environment variables from .env file
-    let envs = parse_env_file("./.env")?;
-
-    for (key, value) in envs {
-        let key = key.to_uppercase();
-        let line = format!(
-            "#[allow(dead_code)] pub(crate) const {}: &str = \"{}\";\n",
-            key, value,
+    println!(
+        "cargo:rerun-if-env-changed={}",
+        PROFILE_VAR
+    );
+    for key in SCHEMA {
+        println!(
+            "cargo:rerun-if-env-changed={}",
+            key.name
         );
-        file.write_all(line.as_bytes())?;
     }
 
+    let profile = select_profile()?;
+
+    // NOTE: A later file overrides an earlier one
+    let mut envs = BTreeMap::new();
+    for path in env_files(&profile) {
+        // NOTE: Also watch a missing file, so that creating it later is
+        // picked up by the next build
+        println!("cargo:rerun-if-changed={}", path);
+        envs.extend(
+            read_env_file(&path)?
+                .into_iter()
+                .map(|(key, value)| (key.to_uppercase(), value)),
+        );
+    }
+
+    for key in envs.keys() {
+        if !SCHEMA
+            .iter()
+            .any(|known| known.name == key)
+        {
+            println!(
+                "cargo:warning={} is not a known key and is ignored",
+                key
+            );
+        }
+    }
+
+    // NOTE: Report every invalid key at once rather than the first one
+    let mut values = Vec::new();
+    let mut errors = Vec::new();
+    for key in SCHEMA {
+        match resolve(key, &envs) {
+            | Ok(value) => values.push((key, value)),
+            | Err(error) => errors.push(error),
+        }
+    }
+    if !errors.is_empty() {
+        bail!(
+            "Invalid build configuration:\n  - {}\n\
+            Set them in {} or as environment variables.",
+            errors.join("\n  - "),
+            env_files(&profile).join(", ")
+        );
+    }
+
+    write_if_changed(
+        GENERATED_PATH,
+        &generate(&profile, &values),
+    )?;
+
     Ok(())
 }
 
-fn parse_env_file(file_path: &str) -> anyhow::Result<HashMap<String, String>> {
-    let path = Path::new(file_path);
-    let file = File::open(path)?;
-    let lines = io::BufReader::new(file).lines();
-
-    let mut env_map = HashMap::new();
-    lines.for_each(|line| {
-        if let Ok(line) = line {
-            if !line.trim().is_empty() && !line.starts_with('#') {
-                let parts: Vec<&str> = line.splitn(2, '=').collect();
-                if parts.len() == 2 {
-                    env_map.insert(
-                        parts[0].to_string(),
-                        parts[1]
-                            .to_string()
-                            .replace('\"', ""),
-                    );
-                }
+/// Selects the profile by [`PROFILE_VAR`], then by a cargo feature in
+/// [`PROFILE_FEATURES`], then falls back to [`DEFAULT_PROFILE`].
+fn select_profile() -> anyhow::Result<String> {
+    let profile = match std::env::var(PROFILE_VAR) {
+        | Ok(profile) if !profile.trim().is_empty() => {
+            profile.trim().to_string()
+        },
+        | _ => {
+            let features = PROFILE_FEATURES
+                .iter()
+                .filter(|feature| {
+                    std::env::var_os(format!(
+                        "CARGO_FEATURE_{}",
+                        feature.to_uppercase()
+                    ))
+                    .is_some()
+                })
+                .collect::<Vec<_>>();
+            match features.as_slice() {
+                | [] => DEFAULT_PROFILE.to_string(),
+                | [feature] => feature.to_string(),
+                | _ => bail!(
+                    "Only one profile feature can be enabled, got {:?}",
+                    features
+                ),
             }
-        }
-    });
+        },
+    };
+
+    // NOTE: The profile is a part of a file name
+    if profile.is_empty()
+        || !profile
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        bail!(
+            "{} must consist of letters, digits, '-' and '_', got {:?}",
+            PROFILE_VAR,
+            profile
+        );
+    }
 
-    Ok(env_map)
+    Ok(profile)
+}
+
+fn env_files(profile: &str) -> Vec<String> {
+    vec![
+        ENV_FILE.to_string(),
+        format!("{}.{}", ENV_FILE, profile),
+        format!("{}.local", ENV_FILE),
+    ]
+}
+
+/// Reads a dotenv file, where a missing file has no keys.
+fn read_env_file(path: &str) -> anyhow::Result<BTreeMap<String, String>> {
+    match std::fs::read_to_string(path) {
+        | Ok(source) => parse_env(&source)
+            .with_context(|| format!("Failed to parse {}", path)),
+        | Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+            Ok(BTreeMap::new())
+        },
+        | Err(error) => {
+            Err(error).with_context(|| format!("Failed to read {}", path))
+        },
+    }
+}
+
+/// Looks up the key in the env files, then in the process environment,
+/// e.g. on CI where no `.env` exists.
+fn resolve(
+    key: &Key,
+    envs: &BTreeMap<String, String>,
+) -> Result<Option<Value>, String> {
+    let value = envs
+        .get(key.name)
+        .cloned()
+        .or_else(|| std::env::var(key.name).ok());
+
+    let value = match value {
+        | Some(value) if !value.trim().is_empty() => value,
+        | Some(_) if key.required => {
+            return Err(format!(
+                "{} must not be empty",
+                key.name
+            ));
+        },
+        | None if key.required => {
+            return Err(format!(
+                "{} is required but not set",
+                key.name
+            ));
+        },
+        | _ => return Ok(None),
+    };
+
+    match key.kind {
+        | Kind::NonEmpty => Ok(Some(Value::Str(value))),
+        | Kind::Url if is_http_url(&value) => Ok(Some(Value::Str(value))),
+        | Kind::Url => Err(format!(
+            "{} must be a URL such as https://example.com, got {:?}",
+            key.name, value
+        )),
+        | Kind::Bool => match value.trim() {
+            | "true" | "1" => Ok(Some(Value::Bool(true))),
+            | "false" | "0" => Ok(Some(Value::Bool(false))),
+            | _ => Err(format!(
+                "{} must be true or false, got {:?}",
+                key.name, value
+            )),
+        },
+    }
+}
+
+fn is_http_url(value: &str) -> bool {
+    value
+        .strip_prefix("https://")
+        .or_else(|| value.strip_prefix("http://"))
+        .map(|rest| {
+            !rest.is_empty()
+                && !rest.starts_with('/')
+                && !rest.contains(char::is_whitespace)
+        })
+        .unwrap_or(false)
+}
+
+/// Generates a struct with a field per key, sorted by the schema, with
+/// values escaped as Rust literals.
+fn generate(
+    profile: &str,
+    values: &[(&Key, Option<Value>)],
+) -> String {
+    let mut code = String::from(
+        "// @generated by build.rs from .env and the environment\n\n",
+    );
+
+    let _ = writeln!(
+        code,
+        "/// The profile the app was built with, e.g. \"production\".\n\
+        pub(crate) const PROFILE: &str = {:?};\n",
+        profile
+    );
+
+    code.push_str("#[derive(Clone, Copy, Debug)]\n");
+    code.push_str("pub(crate) struct BuildConfig {\n");
+    for (key, _) in values {
+        let field_type = match (key.kind, key.required) {
+            | (Kind::Bool, true) => "bool",
+            | (Kind::Bool, false) => "Option<bool>",
+            | (_, true) => "&'static str",
+            | (_, false) => "Option<&'static str>",
+        };
+        let _ = writeln!(
+            code,
+            "    pub(crate) {}: {},",
+            key.name.to_lowercase(),
+            field_type
+        );
+    }
+    code.push_str("}\n\n");
+
+    code.push_str(
+        "pub(crate) const BUILD_CONFIG: BuildConfig = BuildConfig {\n",
+    );
+    for (key, value) in values {
+        let literal = match value {
+            | Some(Value::Str(value)) => format!("{:?}", value),
+            | Some(Value::Bool(value)) => value.to_string(),
+            | None => "None".to_string(),
+        };
+        let literal = match value {
+            | Some(_) if !key.required => format!("Some({})", literal),
+            | _ => literal,
+        };
+        let _ = writeln!(
+            code,
+            "    {}: {},",
+            key.name.to_lowercase(),
+            literal
+        );
+    }
+    code.push_str("};\n");
+
+    code
+}
+
+// NOTE: Rewriting the same content would rebuild the crate every time
+fn write_if_changed(
+    path: &str,
+    content: &str,
+) -> anyhow::Result<()> {
+    let path = Path::new(path);
+    if std::fs::read_to_string(path)
+        .ok()
+        .as_deref()
+        == Some(content)
+    {
+        return Ok(());
+    }
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, content)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+
+    Ok(())
 }
@@ -1,11 +1,815 @@
-use regex::Regex;
+// NOTE: Firebase Auth rejects passwords shorter than 6 characters
+const FIREBASE_MIN_PASSWORD_LENGTH: usize = 6;
+const FIREBASE_MAX_PASSWORD_LENGTH: usize = 4096;
 
-pub(crate) fn is_valid_password(password: String) -> bool {
-    password.len() >= 6
+const COMMON_PASSWORDS: &[&str] = &[
+    "123456",
+    "1234567",
+    "12345678",
+    "123456789",
+    "1234567890",
+    "123123",
+    "111111",
+    "000000",
+    "654321",
+    "666666",
+    "121212",
+    "112233",
+    "123321",
+    "987654321",
+    "password",
+    "password1",
+    "password123",
+    "passw0rd",
+    "p@ssw0rd",
+    "qwerty",
+    "qwerty123",
+    "qwertyuiop",
+    "1q2w3e4r",
+    "1qaz2wsx",
+    "zaq12wsx",
+    "asdfghjkl",
+    "abc123",
+    "abcd1234",
+    "a1b2c3d4",
+    "aa123456",
+    "iloveyou",
+    "admin",
+    "admin123",
+    "welcome",
+    "welcome1",
+    "letmein",
+    "monkey",
+    "dragon",
+    "football",
+    "baseball",
+    "superman",
+    "batman",
+    "master",
+    "shadow",
+    "sunshine",
+    "princess",
+    "trustno1",
+    "starwars",
+    "whatever",
+    "freedom",
+    "login",
+    "secret",
+    "changeme",
+    "default",
+    "guest",
+    "test1234",
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum CharacterClass {
+    Lowercase,
+    Uppercase,
+    Digit,
+    Symbol,
+}
+
+impl CharacterClass {
+    fn matches(
+        &self,
+        character: char,
+    ) -> bool {
+        match self {
+            | CharacterClass::Lowercase => character.is_lowercase(),
+            | CharacterClass::Uppercase => character.is_uppercase(),
+            | CharacterClass::Digit => character.is_numeric(),
+            | CharacterClass::Symbol => !character.is_alphanumeric(),
+        }
+    }
+
+    fn pool_size(&self) -> f64 {
+        match self {
+            | CharacterClass::Lowercase => 26.0,
+            | CharacterClass::Uppercase => 26.0,
+            | CharacterClass::Digit => 10.0,
+            | CharacterClass::Symbol => 33.0,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            | CharacterClass::Lowercase => "a lowercase letter",
+            | CharacterClass::Uppercase => "an uppercase letter",
+            | CharacterClass::Digit => "a digit",
+            | CharacterClass::Symbol => "a symbol",
+        }
+    }
+}
+
+const ALL_CHARACTER_CLASSES: [CharacterClass; 4] = [
+    CharacterClass::Lowercase,
+    CharacterClass::Uppercase,
+    CharacterClass::Digit,
+    CharacterClass::Symbol,
+];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum PasswordRule {
+    MinLength(usize),
+    MaxLength(usize),
+    Contains(CharacterClass),
+    NotContainsEmail,
+    NotCommon,
+}
+
+impl PasswordRule {
+    pub(crate) fn description(&self) -> String {
+        match self {
+            | PasswordRule::MinLength(length) => {
+                format!("At least {} characters", length)
+            },
+            | PasswordRule::MaxLength(length) => {
+                format!("At most {} characters", length)
+            },
+            | PasswordRule::Contains(class) => {
+                format!("Contains {}", class.name())
+            },
+            | PasswordRule::NotContainsEmail => {
+                "Does not contain your e-mail address".to_string()
+            },
+            | PasswordRule::NotCommon => {
+                "Is not a commonly used password".to_string()
+            },
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum PasswordStrength {
+    VeryWeak,
+    Weak,
+    Fair,
+    Strong,
+    VeryStrong,
+}
+
+impl PasswordStrength {
+    pub(crate) fn label(&self) -> &'static str {
+        match self {
+            | PasswordStrength::VeryWeak => "Very weak",
+            | PasswordStrength::Weak => "Weak",
+            | PasswordStrength::Fair => "Fair",
+            | PasswordStrength::Strong => "Strong",
+            | PasswordStrength::VeryStrong => "Very strong",
+        }
+    }
+
+    /// Progress of the strength meter in `0.0..=1.0`.
+    pub(crate) fn progress(&self) -> f32 {
+        match self {
+            | PasswordStrength::VeryWeak => 0.1,
+            | PasswordStrength::Weak => 0.25,
+            | PasswordStrength::Fair => 0.5,
+            | PasswordStrength::Strong => 0.75,
+            | PasswordStrength::VeryStrong => 1.0,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct PasswordPolicy {
+    pub(crate) min_length: usize,
+    pub(crate) max_length: usize,
+    pub(crate) required_classes: Vec<CharacterClass>,
+    pub(crate) forbid_email: bool,
+    pub(crate) forbid_common: bool,
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        Self {
+            min_length: 8,
+            max_length: FIREBASE_MAX_PASSWORD_LENGTH,
+            required_classes: vec![
+                CharacterClass::Lowercase,
+                CharacterClass::Uppercase,
+                CharacterClass::Digit,
+            ],
+            forbid_email: true,
+            forbid_common: true,
+        }
+    }
+}
+
+impl PasswordPolicy {
+    /// Lists all rules of this policy in display order.
+    pub(crate) fn rules(&self) -> Vec<PasswordRule> {
+        let mut rules = vec![PasswordRule::MinLength(
+            self.min_length
+                .max(FIREBASE_MIN_PASSWORD_LENGTH),
+        )];
+        rules.push(PasswordRule::MaxLength(
+            self.max_length
+                .min(FIREBASE_MAX_PASSWORD_LENGTH),
+        ));
+        rules.extend(
+            self.required_classes
+                .iter()
+                .map(|class| PasswordRule::Contains(*class)),
+        );
+        if self.forbid_email {
+            rules.push(PasswordRule::NotContainsEmail);
+        }
+        if self.forbid_common {
+            rules.push(PasswordRule::NotCommon);
+        }
+
+        rules
+    }
+
+    pub(crate) fn is_satisfied(
+        &self,
+        rule: &PasswordRule,
+        password: &str,
+        email: Option<&str>,
+    ) -> bool {
+        match rule {
+            | PasswordRule::MinLength(length) => {
+                password.chars().count() >= *length
+            },
+            | PasswordRule::MaxLength(length) => {
+                password.chars().count() <= *length
+            },
+            | PasswordRule::Contains(class) => password
+                .chars()
+                .any(|character| class.matches(character)),
+            | PasswordRule::NotContainsEmail => {
+                !contains_email(password, email)
+            },
+            | PasswordRule::NotCommon => !is_common_password(password),
+        }
+    }
+
+    /// Returns the rules that the password violates.
+    pub(crate) fn validate(
+        &self,
+        password: &str,
+        email: Option<&str>,
+    ) -> Vec<PasswordRule> {
+        self.rules()
+            .into_iter()
+            .filter(|rule| !self.is_satisfied(rule, password, email))
+            .collect()
+    }
+
+    pub(crate) fn is_valid(
+        &self,
+        password: &str,
+        email: Option<&str>,
+    ) -> bool {
+        self.validate(password, email)
+            .is_empty()
+    }
+
+    /// Estimates the strength by the entropy of used character classes.
+    pub(crate) fn strength(
+        &self,
+        password: &str,
+        email: Option<&str>,
+    ) -> PasswordStrength {
+        if password.is_empty()
+            || is_common_password(password)
+            || contains_email(password, email)
+        {
+            return PasswordStrength::VeryWeak;
+        }
+
+        let pool_size: f64 = ALL_CHARACTER_CLASSES
+            .iter()
+            .filter(|class| {
+                password
+                    .chars()
+                    .any(|character| class.matches(character))
+            })
+            .map(|class| class.pool_size())
+            .sum();
+        let entropy = password.chars().count() as f64 * pool_size.log2();
+
+        match entropy {
+            | bits if bits < 28.0 => PasswordStrength::VeryWeak,
+            | bits if bits < 36.0 => PasswordStrength::Weak,
+            | bits if bits < 60.0 => PasswordStrength::Fair,
+            | bits if bits < 128.0 => PasswordStrength::Strong,
+            | _ => PasswordStrength::VeryStrong,
+        }
+    }
+}
+
+fn is_common_password(password: &str) -> bool {
+    let password = password.to_lowercase();
+    COMMON_PASSWORDS.contains(&password.as_str())
+}
+
+fn contains_email(
+    password: &str,
+    email: Option<&str>,
+) -> bool {
+    let local_part = match email.and_then(|email| email.split('@').next()) {
+        | Some(local_part) => local_part.to_lowercase(),
+        | None => return false,
+    };
+
+    // NOTE: Too short local parts match too many passwords
+    local_part.chars().count() >= 3
+        && password
+            .to_lowercase()
+            .contains(&local_part)
+}
+
+const MAX_LOCAL_PART_LENGTH: usize = 64;
+const MAX_DOMAIN_LENGTH: usize = 253;
+const MAX_DOMAIN_LABEL_LENGTH: usize = 63;
+const MAX_EMAIL_LENGTH: usize = 254;
+const LOCAL_PART_SYMBOLS: &str = "!#$%&'*+-/=?^_`{|}~";
+
+const COMMON_EMAIL_DOMAINS: &[&str] = &[
+    "gmail.com",
+    "googlemail.com",
+    "yahoo.com",
+    "yahoo.co.jp",
+    "hotmail.com",
+    "outlook.com",
+    "outlook.jp",
+    "live.com",
+    "msn.com",
+    "icloud.com",
+    "me.com",
+    "aol.com",
+    "protonmail.com",
+    "proton.me",
+    "gmx.com",
+    "mail.com",
+    "yandex.com",
+    "qq.com",
+    "docomo.ne.jp",
+    "ezweb.ne.jp",
+    "au.com",
+    "softbank.ne.jp",
+    "i.softbank.jp",
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum EmailError {
+    Empty,
+    MissingAt,
+    EmptyLocalPart,
+    LocalPartTooLong,
+    InvalidLocalPart,
+    UnterminatedQuote,
+    EmptyDomain,
+    InvalidDomain,
+    EmptyDomainLabel,
+    DomainLabelTooLong,
+    MissingTopLevelDomain,
+    TooLong,
+}
+
+impl EmailError {
+    pub(crate) fn description(&self) -> &'static str {
+        match self {
+            | EmailError::Empty => "Please enter an e-mail address.",
+            | EmailError::MissingAt => "The address must contain an \"@\".",
+            | EmailError::EmptyLocalPart => {
+                "The part before \"@\" is missing."
+            },
+            | EmailError::LocalPartTooLong => {
+                "The part before \"@\" is longer than 64 characters."
+            },
+            | EmailError::InvalidLocalPart => {
+                "The part before \"@\" contains an invalid character or \
+                 dot."
+            },
+            | EmailError::UnterminatedQuote => {
+                "The quoted part before \"@\" is not closed."
+            },
+            | EmailError::EmptyDomain => "The part after \"@\" is missing.",
+            | EmailError::InvalidDomain => {
+                "The domain contains an invalid character."
+            },
+            | EmailError::EmptyDomainLabel => {
+                "The domain contains consecutive or trailing dots."
+            },
+            | EmailError::DomainLabelTooLong => {
+                "A part of the domain is longer than 63 characters."
+            },
+            | EmailError::MissingTopLevelDomain => {
+                "The domain is missing a top-level domain such as \".com\"."
+            },
+            | EmailError::TooLong => "The address is too long.",
+        }
+    }
+}
+
+/// An e-mail address parsed along RFC 5321 and RFC 6531.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct EmailAddress {
+    pub(crate) local_part: String,
+    /// The domain in ASCII, internationalized labels are punycoded.
+    pub(crate) domain: String,
+}
+
+impl EmailAddress {
+    pub(crate) fn parse(email: &str) -> Result<Self, EmailError> {
+        let email = email.trim();
+        if email.is_empty() {
+            return Err(EmailError::Empty);
+        }
+
+        // NOTE: A quoted local part may contain "@"
+        let (local_part, domain) = match email.rfind('@') {
+            | Some(index) => (&email[..index], &email[index + 1..]),
+            | None => return Err(EmailError::MissingAt),
+        };
+
+        parse_local_part(local_part)?;
+        let domain = parse_domain(domain)?;
+
+        if local_part.len() + 1 + domain.len() > MAX_EMAIL_LENGTH {
+            return Err(EmailError::TooLong);
+        }
+
+        Ok(Self {
+            local_part: local_part.to_string(),
+            domain,
+        })
+    }
+
+    pub(crate) fn to_ascii(&self) -> String {
+        format!("{}@{}", self.local_part, self.domain)
+    }
+}
+
+fn parse_local_part(local_part: &str) -> Result<(), EmailError> {
+    if local_part.is_empty() {
+        return Err(EmailError::EmptyLocalPart);
+    }
+    if local_part.len() > MAX_LOCAL_PART_LENGTH {
+        return Err(EmailError::LocalPartTooLong);
+    }
+
+    if let Some(quoted) = local_part.strip_prefix('"') {
+        return parse_quoted_local_part(quoted);
+    }
+
+    let is_valid_atom = |atom: &str| {
+        !atom.is_empty()
+            && atom.chars().all(|character| {
+                character.is_ascii_alphanumeric()
+                    || LOCAL_PART_SYMBOLS.contains(character)
+                    || (!character.is_ascii()
+                        && !character.is_control()
+                        && !character.is_whitespace())
+            })
+    };
+
+    if local_part
+        .split('.')
+        .all(is_valid_atom)
+    {
+        Ok(())
+    } else {
+        Err(EmailError::InvalidLocalPart)
+    }
+}
+
+fn parse_quoted_local_part(quoted: &str) -> Result<(), EmailError> {
+    let mut characters = quoted.chars();
+    while let Some(character) = characters.next() {
+        match character {
+            | '\\' => {
+                if characters.next().is_none() {
+                    return Err(EmailError::UnterminatedQuote);
+                }
+            },
+            | '"' => {
+                // NOTE: The closing quote must end the local part
+                return match characters.next() {
+                    | None => Ok(()),
+                    | Some(_) => Err(EmailError::InvalidLocalPart),
+                };
+            },
+            | character if character.is_control() => {
+                return Err(EmailError::InvalidLocalPart);
+            },
+            | _ => {},
+        }
+    }
+
+    Err(EmailError::UnterminatedQuote)
+}
+
+fn parse_domain(domain: &str) -> Result<String, EmailError> {
+    if domain.is_empty() {
+        return Err(EmailError::EmptyDomain);
+    }
+    if domain
+        .split('.')
+        .any(|label| label.is_empty())
+    {
+        return Err(EmailError::EmptyDomainLabel);
+    }
+
+    let domain = idna::domain_to_ascii(domain)
+        .map_err(|_| EmailError::InvalidDomain)?;
+    if domain.len() > MAX_DOMAIN_LENGTH {
+        return Err(EmailError::TooLong);
+    }
+
+    let labels: Vec<&str> = domain.split('.').collect();
+    for label in labels.iter() {
+        if label.len() > MAX_DOMAIN_LABEL_LENGTH {
+            return Err(EmailError::DomainLabelTooLong);
+        }
+        if label.starts_with('-')
+            || label.ends_with('-')
+            || !label
+                .chars()
+                .all(|character| {
+                    character.is_ascii_alphanumeric() || character == '-'
+                })
+        {
+            return Err(EmailError::InvalidDomain);
+        }
+    }
+
+    match labels.last() {
+        | Some(top_level_domain)
+            if labels.len() >= 2
+                && top_level_domain.len() >= 2
+                && !top_level_domain
+                    .chars()
+                    .all(|character| character.is_ascii_digit()) =>
+        {
+            Ok(domain)
+        },
+        | _ => Err(EmailError::MissingTopLevelDomain),
+    }
+}
+
+pub(crate) fn is_valid_email(email: &str) -> bool {
+    EmailAddress::parse(email).is_ok()
+}
+
+/// Suggests a correction when the domain looks like a typo of a
+/// common mail provider, e.g. "gmial.com" to "gmail.com".
+pub(crate) fn suggest_email(email: &str) -> Option<String> {
+    let email = email.trim();
+    let index = email.rfind('@')?;
+    let (local_part, domain) = (&email[..index], &email[index + 1..]);
+    if local_part.is_empty() || domain.is_empty() {
+        return None;
+    }
+
+    let domain = domain.to_lowercase();
+    if COMMON_EMAIL_DOMAINS.contains(&domain.as_str()) {
+        return None;
+    }
+
+    // NOTE: Allow only one edit for short domains to avoid false hits
+    let max_distance = if domain.len() <= 6 { 1 } else { 2 };
+
+    COMMON_EMAIL_DOMAINS
+        .iter()
+        .map(|candidate| (candidate, edit_distance(&domain, candidate)))
+        .filter(|(_, distance)| *distance <= max_distance)
+        .min_by_key(|(_, distance)| *distance)
+        .map(|(candidate, _)| format!("{}@{}", local_part, candidate))
+}
+
+/// Optimal string alignment distance, which counts a swap of two
+/// adjacent characters as one edit.
+fn edit_distance(
+    left: &str,
+    right: &str,
+) -> usize {
+    let left: Vec<char> = left.chars().collect();
+    let right: Vec<char> = right.chars().collect();
+    let width = right.len() + 1;
+    let mut table = vec![0; (left.len() + 1) * width];
+
+    for i in 0..=left.len() {
+        table[i * width] = i;
+    }
+    for (j, cell) in table
+        .iter_mut()
+        .take(width)
+        .enumerate()
+    {
+        *cell = j;
+    }
+
+    for i in 1..=left.len() {
+        for j in 1..=right.len() {
+            let cost = usize::from(left[i - 1] != right[j - 1]);
+            let mut distance = (table[(i - 1) * width + j] + 1)
+                .min(table[i * width + j - 1] + 1)
+                .min(table[(i - 1) * width + j - 1] + cost);
+            if i > 1
+                && j > 1
+                && left[i - 1] == right[j - 2]
+                && left[i - 2] == right[j - 1]
+            {
+                distance = distance.min(table[(i - 2) * width + j - 2] + 1);
+            }
+            table[i * width + j] = distance;
+        }
+    }
+
+    table[left.len() * width + right.len()]
 }
 
-pub fn is_valid_email(email: String) -> bool {
-    Regex::new(r"^[a-zA-Z0-9_.+-]+@[a-zA-Z0-9-]+\.[a-zA-Z0-9-.]+$")
-        .unwrap()
-        .is_match(&email)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_satisfied(
+        rule: PasswordRule,
+        password: &str,
+    ) -> bool {
+        PasswordPolicy::default().is_satisfied(&rule, password, None)
+    }
+
+    #[test]
+    fn length_is_counted_in_characters() {
+        // NOTE: 7 characters but 17 bytes
+        assert!(!is_satisfied(
+            PasswordRule::MinLength(8),
+            "パスワードA1"
+        ));
+        assert!(is_satisfied(
+            PasswordRule::MinLength(8),
+            "パスワードAb1"
+        ));
+
+        let long = "あ".repeat(FIREBASE_MAX_PASSWORD_LENGTH);
+        assert!(is_satisfied(
+            PasswordRule::MaxLength(FIREBASE_MAX_PASSWORD_LENGTH),
+            &long
+        ));
+        assert!(!is_satisfied(
+            PasswordRule::MaxLength(FIREBASE_MAX_PASSWORD_LENGTH),
+            &format!("{}あ", long)
+        ));
+    }
+
+    #[test]
+    fn character_classes() {
+        let contains = |class, password| {
+            is_satisfied(PasswordRule::Contains(class), password)
+        };
+
+        assert!(contains(CharacterClass::Lowercase, "ABCd"));
+        assert!(!contains(CharacterClass::Lowercase, "ABC1"));
+        assert!(contains(CharacterClass::Uppercase, "abcD"));
+        assert!(!contains(CharacterClass::Uppercase, "abc1"));
+        assert!(contains(CharacterClass::Digit, "abc1"));
+        assert!(!contains(CharacterClass::Digit, "abcD"));
+        assert!(contains(CharacterClass::Symbol, "abc!"));
+        assert!(!contains(CharacterClass::Symbol, "abc1"));
+    }
+
+    #[test]
+    fn password_must_not_contain_email() {
+        let policy = PasswordPolicy::default();
+        let rule = PasswordRule::NotContainsEmail;
+
+        assert!(!policy.is_satisfied(
+            &rule,
+            "Alice2024X",
+            Some("alice@example.com")
+        ));
+        assert!(policy.is_satisfied(
+            &rule,
+            "Bob2024X",
+            Some("alice@example.com")
+        ));
+        // NOTE: Too short local parts are not checked
+        assert!(policy.is_satisfied(&rule, "Ab2024X", Some("ab@example.com")));
+        assert!(policy.is_satisfied(&rule, "Alice2024X", None));
+    }
+
+    #[test]
+    fn common_passwords_are_rejected_case_insensitively() {
+        assert!(!is_satisfied(PasswordRule::NotCommon, "Password123"));
+        assert!(!is_satisfied(PasswordRule::NotCommon, "QWERTY"));
+        assert!(is_satisfied(PasswordRule::NotCommon, "Tr0ub4dour&3"));
+    }
+
+    #[test]
+    fn min_length_is_not_below_firebase_minimum() {
+        let policy = PasswordPolicy {
+            min_length: 4,
+            ..PasswordPolicy::default()
+        };
+
+        assert_eq!(
+            policy.rules()[0],
+            PasswordRule::MinLength(FIREBASE_MIN_PASSWORD_LENGTH)
+        );
+    }
+
+    #[test]
+    fn validate_lists_violated_rules() {
+        let policy = PasswordPolicy::default();
+
+        assert_eq!(
+            policy.validate("abc", None),
+            vec![
+                PasswordRule::MinLength(8),
+                PasswordRule::Contains(CharacterClass::Uppercase),
+                PasswordRule::Contains(CharacterClass::Digit),
+            ]
+        );
+        assert!(policy.is_valid("Tr0ub4dour", None));
+    }
+
+    #[test]
+    fn strength() {
+        let policy = PasswordPolicy::default();
+        let strength = |password| policy.strength(password, None);
+
+        assert_eq!(strength(""), PasswordStrength::VeryWeak);
+        assert_eq!(strength("password123"), PasswordStrength::VeryWeak);
+        assert_eq!(strength("abcd"), PasswordStrength::VeryWeak);
+        assert_eq!(strength("abcdef"), PasswordStrength::Weak);
+        assert_eq!(strength("Abcdefgh1"), PasswordStrength::Fair);
+        assert_eq!(
+            strength("Tr0ub4dour&3xyzAbc!"),
+            PasswordStrength::Strong
+        );
+        assert_eq!(
+            strength("Tr0ub4dour&3xyzAbc!Tr0ub4dour&3"),
+            PasswordStrength::VeryStrong
+        );
+        assert_eq!(
+            policy.strength("Alice2024X!", Some("alice@example.com")),
+            PasswordStrength::VeryWeak
+        );
+    }
+
+    #[test]
+    fn email_address_is_parsed() {
+        assert_eq!(
+            EmailAddress::parse(" user.name+tag@example.com "),
+            Ok(EmailAddress {
+                local_part: "user.name+tag".to_string(),
+                domain: "example.com".to_string(),
+            })
+        );
+        assert!(is_valid_email("\"john doe\"@example.com"));
+        assert!(is_valid_email("\"a@b\"@example.com"));
+        assert_eq!(
+            EmailAddress::parse("user@bücher.de").map(|email| email.domain),
+            Ok("xn--bcher-kva.de".to_string())
+        );
+        assert!(is_valid_email("ユーザー@例え.jp"));
+    }
+
+    #[test]
+    fn invalid_email_address_is_rejected() {
+        let error = |email| EmailAddress::parse(email).unwrap_err();
+
+        assert_eq!(error(""), EmailError::Empty);
+        assert_eq!(error("a.example.com"), EmailError::MissingAt);
+        assert_eq!(error("@example.com"), EmailError::EmptyLocalPart);
+        assert_eq!(error("a..b@example.com"), EmailError::InvalidLocalPart);
+        assert_eq!(error("\"ab@example.com"), EmailError::UnterminatedQuote);
+        assert_eq!(error("a@"), EmailError::EmptyDomain);
+        assert_eq!(error("a@b..c"), EmailError::EmptyDomainLabel);
+        assert_eq!(error("a@example."), EmailError::EmptyDomainLabel);
+        assert_eq!(error("a@-example.com"), EmailError::InvalidDomain);
+        assert_eq!(error("a@example"), EmailError::MissingTopLevelDomain);
+        assert_eq!(
+            error(&format!("{}@example.com", "a".repeat(65))),
+            EmailError::LocalPartTooLong
+        );
+    }
+
+    #[test]
+    fn typo_of_common_domain_is_suggested() {
+        assert_eq!(
+            suggest_email("user@gmial.com"),
+            Some("user@gmail.com".to_string())
+        );
+        assert_eq!(
+            suggest_email("user@hotmial.com"),
+            Some("user@hotmail.com".to_string())
+        );
+        assert_eq!(suggest_email("user@gmail.com"), None);
+        assert_eq!(suggest_email("user@example.com"), None);
+        assert_eq!(suggest_email("@gmial.com"), None);
+    }
+
+    #[test]
+    fn edit_distance_counts_swap_as_one_edit() {
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("abc", "abc"), 0);
+        assert_eq!(edit_distance("abc", "acb"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
 }
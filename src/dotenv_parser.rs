@@ -0,0 +1,246 @@
+//! A parser of dotenv files, used by `build.rs`.
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, bail};
+
+/// Parses dotenv source, where a later assignment overrides an earlier
+/// one.
+///
+/// Supports `export` prefixes, comments, unquoted values with inline
+/// comments, single-quoted literals, and double-quoted values with
+/// escapes. Quoted values may span multiple lines.
+pub(crate) fn parse_env(source: &str) -> anyhow::Result<BTreeMap<String, String>> {
+    let mut envs = BTreeMap::new();
+    let mut lines = source.lines().enumerate();
+
+    while let Some((index, line)) = lines.next() {
+        let line_number = index + 1;
+        // NOTE: The end is kept for a quoted value that spans lines
+        let line = line.trim_start();
+        if line.trim_end().is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let line = line
+            .strip_prefix("export ")
+            .map(str::trim_start)
+            .unwrap_or(line);
+
+        let (key, rest) = line
+            .split_once('=')
+            .ok_or(anyhow!(
+                "Line {}: expected KEY=VALUE",
+                line_number
+            ))?;
+        let key = key.trim();
+        if !is_valid_key(key) {
+            bail!(
+                "Line {}: invalid key {:?}",
+                line_number,
+                key
+            );
+        }
+
+        let rest = rest.trim_start();
+        let value = match rest.chars().next() {
+            | Some(quote @ ('"' | '\'')) => {
+                // NOTE: Join the following lines until the closing quote
+                let mut quoted = rest[1..].to_string();
+                let (value, trailing) = loop {
+                    if let Some(parsed) = parse_quoted(&quoted, quote) {
+                        break parsed;
+                    }
+                    match lines.next() {
+                        | Some((_, next)) => {
+                            quoted.push('\n');
+                            quoted.push_str(next);
+                        },
+                        | None => bail!(
+                            "Line {}: unterminated {} quote",
+                            line_number,
+                            quote
+                        ),
+                    }
+                };
+
+                let trailing = trailing.trim();
+                if !trailing.is_empty() && !trailing.starts_with('#') {
+                    bail!(
+                        "Line {}: unexpected {:?} after the quoted value",
+                        line_number,
+                        trailing
+                    );
+                }
+                value
+            },
+            | _ => strip_inline_comment(rest)
+                .trim_end()
+                .to_string(),
+        };
+
+        envs.insert(key.to_string(), value);
+    }
+
+    Ok(envs)
+}
+
+fn is_valid_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Returns the value up to the closing quote and the text after it, or
+/// `None` if the quote is not closed yet.
+fn parse_quoted(
+    text: &str,
+    quote: char,
+) -> Option<(String, &str)> {
+    let mut value = String::new();
+    let mut chars = text.char_indices();
+
+    while let Some((index, c)) = chars.next() {
+        match c {
+            | c if c == quote => {
+                return Some((value, &text[index + c.len_utf8()..]));
+            },
+            // NOTE: Single-quoted values are taken literally
+            | '\\' if quote == '"' => match chars.next() {
+                | Some((_, 'n')) => value.push('\n'),
+                | Some((_, 'r')) => value.push('\r'),
+                | Some((_, 't')) => value.push('\t'),
+                | Some((_, escaped @ ('"' | '\\' | '$'))) => {
+                    value.push(escaped)
+                },
+                | Some((_, other)) => {
+                    value.push('\\');
+                    value.push(other);
+                },
+                | None => return None,
+            },
+            | c => value.push(c),
+        }
+    }
+
+    None
+}
+
+// NOTE: "#" starts a comment only after whitespace, e.g. "a#b" is kept
+fn strip_inline_comment(value: &str) -> &str {
+    let mut previous = None;
+    for (index, c) in value.char_indices() {
+        if c == '#' && previous.map_or(true, char::is_whitespace) {
+            return &value[..index];
+        }
+        previous = Some(c);
+    }
+
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(source: &str) -> BTreeMap<String, String> {
+        parse_env(source).unwrap()
+    }
+
+    fn value(
+        source: &str,
+        key: &str,
+    ) -> String {
+        parse(source)
+            .remove(key)
+            .unwrap()
+    }
+
+    #[test]
+    fn unquoted_value_is_trimmed() {
+        assert_eq!(value("KEY = value  ", "KEY"), "value");
+        assert_eq!(value("  KEY=value\r\n", "KEY"), "value");
+        assert_eq!(value("KEY=", "KEY"), "");
+    }
+
+    #[test]
+    fn comments_and_blank_lines_are_skipped() {
+        let envs = parse("# comment\n\n   \n  # indented\nKEY=value\n");
+
+        assert_eq!(envs.len(), 1);
+        assert_eq!(envs["KEY"], "value");
+    }
+
+    #[test]
+    fn inline_comment_starts_after_whitespace() {
+        assert_eq!(value("KEY=value # comment", "KEY"), "value");
+        assert_eq!(value("KEY=a#b", "KEY"), "a#b");
+        assert_eq!(value("KEY=#comment", "KEY"), "");
+        assert_eq!(value("KEY=\"a # b\" # comment", "KEY"), "a # b");
+    }
+
+    #[test]
+    fn export_prefix_is_ignored() {
+        assert_eq!(value("export KEY=value", "KEY"), "value");
+        assert_eq!(value("export   KEY='value'", "KEY"), "value");
+    }
+
+    #[test]
+    fn double_quoted_value_is_unescaped() {
+        assert_eq!(
+            value(r#"KEY="a\nb\tc\"d\\e\$f""#, "KEY"),
+            "a\nb\tc\"d\\e$f"
+        );
+        // NOTE: Unknown escapes are kept as they are
+        assert_eq!(value(r#"KEY="a\qb""#, "KEY"), "a\\qb");
+        assert_eq!(value("KEY=\"  padded  \"", "KEY"), "  padded  ");
+    }
+
+    #[test]
+    fn single_quoted_value_is_literal() {
+        assert_eq!(value(r"KEY='a\nb $c'", "KEY"), r"a\nb $c");
+    }
+
+    #[test]
+    fn quoted_value_spans_lines() {
+        let envs = parse("KEY=\"first  \n  second\nthird\"\nNEXT=value");
+
+        assert_eq!(envs["KEY"], "first  \n  second\nthird");
+        assert_eq!(envs["NEXT"], "value");
+
+        assert_eq!(value("KEY='a\n\nb'", "KEY"), "a\n\nb");
+    }
+
+    #[test]
+    fn later_assignment_overrides_earlier_one() {
+        assert_eq!(value("KEY=first\nKEY=second", "KEY"), "second");
+    }
+
+    #[test]
+    fn unterminated_quote_is_rejected() {
+        let error = parse_env("KEY=\"value\nNEXT=value")
+            .unwrap_err()
+            .to_string();
+
+        assert_eq!(error, "Line 1: unterminated \" quote");
+        assert!(parse_env("KEY='value").is_err());
+        assert!(parse_env(r#"KEY="value\"#).is_err());
+    }
+
+    #[test]
+    fn text_after_quoted_value_is_rejected() {
+        assert!(parse_env("KEY=\"value\" extra").is_err());
+    }
+
+    #[test]
+    fn invalid_line_is_rejected() {
+        assert!(parse_env("KEY").is_err());
+        assert!(parse_env("1KEY=value").is_err());
+        assert!(parse_env("MY-KEY=value").is_err());
+        assert_eq!(
+            parse_env("A=1\nB")
+                .unwrap_err()
+                .to_string(),
+            "Line 2: expected KEY=VALUE"
+        );
+    }
+}
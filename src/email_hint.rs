@@ -0,0 +1,63 @@
+use dioxus::prelude::{
+    component, dioxus_elements, render, Element, EventHandler,
+    GlobalAttributes, IntoDynNode, Props, Scope,
+};
+
+use crate::credential::{suggest_email, EmailAddress};
+
+#[allow(non_snake_case)]
+#[component(no_case_check)]
+pub(crate) fn EmailHint<'a>(
+    cx: Scope<'a>,
+    email: String,
+    // NOTE: Called with the suggested address when it is accepted
+    on_accept: EventHandler<'a, String>,
+) -> Element<'a> {
+    if email.is_empty() {
+        return render! { span {} };
+    }
+
+    let suggestion = suggest_email(email);
+
+    render! {
+        match EmailAddress::parse(email) {
+            | Ok(_) => render! {
+                span {
+                    color: "green",
+                    label {
+                        "✓"
+                    }
+                }
+            },
+            | Err(error) => render! {
+                span {
+                    color: "red",
+                    label {
+                        " {error.description()}"
+                    }
+                }
+            },
+        }
+
+        if let Some(suggestion) = suggestion {
+            render! {
+                div {
+                    label {
+                        "Did you mean "
+                    }
+
+                    a {
+                        href: "#",
+                        prevent_default: "onclick",
+                        onclick: move |_| on_accept.call(suggestion.clone()),
+                        "{suggestion}"
+                    }
+
+                    label {
+                        "?"
+                    }
+                }
+            }
+        }
+    }
+}
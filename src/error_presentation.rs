@@ -0,0 +1,446 @@
+use dioxus::prelude::{
+    component, dioxus_elements, fc_to_builder, render, use_state, Element,
+    GlobalAttributes, IntoDynNode, Props, Scope,
+};
+use fars::error::CommonErrorCode;
+use material_dioxus::MatButton;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub(crate) fn color(&self) -> &'static str {
+        match self {
+            | Severity::Info => "gray",
+            | Severity::Warning => "darkorange",
+            | Severity::Error => "red",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Language {
+    English,
+    Japanese,
+}
+
+impl Language {
+    pub(crate) fn detect() -> Self {
+        let language = web_sys::window()
+            .and_then(|window| window.navigator().language())
+            .unwrap_or_default();
+
+        if language.starts_with("ja") {
+            Language::Japanese
+        } else {
+            Language::English
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ErrorMessage {
+    InvalidCredentials,
+    InvalidEmail,
+    WeakPassword,
+    EmailExists,
+    EmailNotFound,
+    UserNotFound,
+    UserDisabled,
+    OperationNotAllowed,
+    TooManyAttempts,
+    CredentialTooOld,
+    SessionExpired,
+    InvalidActionCode,
+    ExpiredActionCode,
+    CredentialAlreadyInUse,
+    OAuthDenied,
+    OAuthCodeExpired,
+    OAuthFailed,
+    AccountMismatch,
+    Network,
+    Unexpected,
+}
+
+impl ErrorMessage {
+    pub(crate) fn localize(
+        &self,
+        language: Language,
+    ) -> &'static str {
+        match language {
+            | Language::English => self.english(),
+            | Language::Japanese => self.japanese(),
+        }
+    }
+
+    fn english(&self) -> &'static str {
+        match self {
+            | ErrorMessage::InvalidCredentials => {
+                "Invalid e-mail address or password."
+            },
+            | ErrorMessage::InvalidEmail => "The e-mail address is invalid.",
+            | ErrorMessage::WeakPassword => "The password is too weak.",
+            | ErrorMessage::EmailExists => {
+                "The e-mail address is already in use by another account."
+            },
+            | ErrorMessage::EmailNotFound => {
+                "There is no account with this e-mail address."
+            },
+            | ErrorMessage::UserNotFound => {
+                "The account was not found. It may have been deleted."
+            },
+            | ErrorMessage::UserDisabled => {
+                "The account has been disabled by an administrator."
+            },
+            | ErrorMessage::OperationNotAllowed => {
+                "This sign-in method is not enabled."
+            },
+            | ErrorMessage::TooManyAttempts => {
+                "Too many attempts. Please try again later or reset your password."
+            },
+            | ErrorMessage::CredentialTooOld => {
+                "Please sign in again to continue."
+            },
+            | ErrorMessage::SessionExpired => {
+                "Your session has expired. Please sign in again."
+            },
+            | ErrorMessage::InvalidActionCode => {
+                "The link is invalid or has already been used."
+            },
+            | ErrorMessage::ExpiredActionCode => "The link has expired.",
+            | ErrorMessage::CredentialAlreadyInUse => {
+                "The credential is already linked to another account."
+            },
+            | ErrorMessage::OAuthDenied => {
+                "The sign-in request was denied."
+            },
+            | ErrorMessage::OAuthCodeExpired => {
+                "The sign-in request has expired. Please try again."
+            },
+            | ErrorMessage::OAuthFailed => {
+                "Failed to sign in with the external provider."
+            },
+            | ErrorMessage::AccountMismatch => {
+                "You signed in with a different account. Please use the account you are signed in with."
+            },
+            | ErrorMessage::Network => {
+                "Network error. Please check your connection."
+            },
+            | ErrorMessage::Unexpected => {
+                "An unexpected error occurred. Please try again."
+            },
+        }
+    }
+
+    fn japanese(&self) -> &'static str {
+        match self {
+            | ErrorMessage::InvalidCredentials => {
+                "メールアドレスまたはパスワードが正しくありません。"
+            },
+            | ErrorMessage::InvalidEmail => "メールアドレスが無効です。",
+            | ErrorMessage::WeakPassword => "パスワードが弱すぎます。",
+            | ErrorMessage::EmailExists => {
+                "このメールアドレスは既に他のアカウントで使用されています。"
+            },
+            | ErrorMessage::EmailNotFound => {
+                "このメールアドレスのアカウントは存在しません。"
+            },
+            | ErrorMessage::UserNotFound => {
+                "アカウントが見つかりません。削除された可能性があります。"
+            },
+            | ErrorMessage::UserDisabled => {
+                "このアカウントは管理者によって無効化されています。"
+            },
+            | ErrorMessage::OperationNotAllowed => {
+                "このサインイン方法は有効になっていません。"
+            },
+            | ErrorMessage::TooManyAttempts => {
+                "試行回数が多すぎます。しばらくしてから再度お試しいただくか、パスワードを再設定してください。"
+            },
+            | ErrorMessage::CredentialTooOld => {
+                "続行するには再度サインインしてください。"
+            },
+            | ErrorMessage::SessionExpired => {
+                "セッションの有効期限が切れました。再度サインインしてください。"
+            },
+            | ErrorMessage::InvalidActionCode => {
+                "リンクが無効か、既に使用されています。"
+            },
+            | ErrorMessage::ExpiredActionCode => {
+                "リンクの有効期限が切れています。"
+            },
+            | ErrorMessage::CredentialAlreadyInUse => {
+                "この認証情報は既に他のアカウントに紐付けられています。"
+            },
+            | ErrorMessage::OAuthDenied => {
+                "サインインのリクエストが拒否されました。"
+            },
+            | ErrorMessage::OAuthCodeExpired => {
+                "サインインのリクエストの有効期限が切れました。再度お試しください。"
+            },
+            | ErrorMessage::OAuthFailed => {
+                "外部プロバイダでのサインインに失敗しました。"
+            },
+            | ErrorMessage::AccountMismatch => {
+                "別のアカウントでサインインしました。現在のアカウントでサインインしてください。"
+            },
+            | ErrorMessage::Network => {
+                "ネットワークエラーです。接続を確認してください。"
+            },
+            | ErrorMessage::Unexpected => {
+                "予期しないエラーが発生しました。再度お試しください。"
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct ErrorPresentation {
+    pub(crate) message: ErrorMessage,
+    pub(crate) severity: Severity,
+    pub(crate) detail: String,
+}
+
+impl ErrorPresentation {
+    pub(crate) fn new(
+        message: ErrorMessage,
+        severity: Severity,
+        detail: String,
+    ) -> Self {
+        Self {
+            message,
+            severity,
+            detail,
+        }
+    }
+
+    pub(crate) fn text(&self) -> &'static str {
+        self.message
+            .localize(Language::detect())
+    }
+}
+
+impl From<&fars::error::Error> for ErrorPresentation {
+    fn from(error: &fars::error::Error) -> Self {
+        let detail = format!("{:?}", error);
+        match error {
+            | fars::error::Error::ApiError {
+                status_code: _,
+                error_code,
+                response: _,
+            } => {
+                let (message, severity) = map_common_error_code(error_code);
+                Self::new(message, severity, detail)
+            },
+            | fars::error::Error::HttpRequestError(_) => {
+                Self::new(ErrorMessage::Network, Severity::Warning, detail)
+            },
+            | _ => Self::new(
+                ErrorMessage::Unexpected,
+                Severity::Error,
+                detail,
+            ),
+        }
+    }
+}
+
+impl From<&google_oauth_rs::error::Error> for ErrorPresentation {
+    fn from(error: &google_oauth_rs::error::Error) -> Self {
+        let detail = format!("{:?}", error);
+        match error {
+            | google_oauth_rs::error::Error::HttpError(_)
+            | google_oauth_rs::error::Error::ReadResponseFailed(_) => {
+                Self::new(ErrorMessage::Network, Severity::Warning, detail)
+            },
+            // NOTE: The authorization code is single use and short lived
+            | google_oauth_rs::error::Error::ApiError {
+                status_code: _,
+                response,
+            } if response.contains("invalid_grant") => Self::new(
+                ErrorMessage::OAuthCodeExpired,
+                Severity::Warning,
+                detail,
+            ),
+            | _ => Self::new(ErrorMessage::OAuthFailed, Severity::Error, detail),
+        }
+    }
+}
+
+impl From<&crate::identity_toolkit::error::Error> for ErrorPresentation {
+    fn from(error: &crate::identity_toolkit::error::Error) -> Self {
+        let detail = format!("{:?}", error);
+        match error {
+            | crate::identity_toolkit::error::Error::HttpError(_)
+            | crate::identity_toolkit::error::Error::ReadResponseFailed(_) => {
+                Self::new(ErrorMessage::Network, Severity::Warning, detail)
+            },
+            | crate::identity_toolkit::error::Error::ApiError {
+                status_code: _,
+                error_code,
+                response: _,
+            } => {
+                let (message, severity) = map_error_code_name(error_code);
+                Self::new(message, severity, detail)
+            },
+            | _ => Self::new(ErrorMessage::Unexpected, Severity::Error, detail),
+        }
+    }
+}
+
+impl From<&anyhow::Error> for ErrorPresentation {
+    fn from(error: &anyhow::Error) -> Self {
+        if let Some(error) = error.downcast_ref::<fars::error::Error>() {
+            error.into()
+        } else if let Some(error) =
+            error.downcast_ref::<google_oauth_rs::error::Error>()
+        {
+            error.into()
+        } else if let Some(error) =
+            error.downcast_ref::<crate::identity_toolkit::error::Error>()
+        {
+            error.into()
+        } else {
+            Self::new(
+                ErrorMessage::Unexpected,
+                Severity::Error,
+                format!("{:?}", error),
+            )
+        }
+    }
+}
+
+fn map_common_error_code(
+    error_code: &CommonErrorCode
+) -> (ErrorMessage, Severity) {
+    match error_code {
+        | CommonErrorCode::InvalidLoginCredentials
+        | CommonErrorCode::InvalidPassword => {
+            (ErrorMessage::InvalidCredentials, Severity::Warning)
+        },
+        | CommonErrorCode::InvalidEmail => {
+            (ErrorMessage::InvalidEmail, Severity::Warning)
+        },
+        | CommonErrorCode::WeakPassword => {
+            (ErrorMessage::WeakPassword, Severity::Warning)
+        },
+        | CommonErrorCode::EmailExists => {
+            (ErrorMessage::EmailExists, Severity::Warning)
+        },
+        | CommonErrorCode::EmailNotFound => {
+            (ErrorMessage::EmailNotFound, Severity::Warning)
+        },
+        | CommonErrorCode::UserNotFound => {
+            (ErrorMessage::UserNotFound, Severity::Error)
+        },
+        | CommonErrorCode::UserDisabled => {
+            (ErrorMessage::UserDisabled, Severity::Error)
+        },
+        | CommonErrorCode::OperationNotAllowed(_) => {
+            (ErrorMessage::OperationNotAllowed, Severity::Error)
+        },
+        | CommonErrorCode::TooManyAttemptsTryLater => {
+            (ErrorMessage::TooManyAttempts, Severity::Warning)
+        },
+        | CommonErrorCode::CredentialTooOldLoginAgain => {
+            (ErrorMessage::CredentialTooOld, Severity::Info)
+        },
+        | CommonErrorCode::TokenExpired
+        | CommonErrorCode::InvalidIdToken
+        | CommonErrorCode::InvalidRefreshToken => {
+            (ErrorMessage::SessionExpired, Severity::Warning)
+        },
+        | CommonErrorCode::InvalidOobCode => {
+            (ErrorMessage::InvalidActionCode, Severity::Warning)
+        },
+        | CommonErrorCode::ExpiredOobCode => {
+            (ErrorMessage::ExpiredActionCode, Severity::Warning)
+        },
+        | CommonErrorCode::FederatedUserIdAlreadyLinked => {
+            (ErrorMessage::CredentialAlreadyInUse, Severity::Warning)
+        },
+        | _ => (ErrorMessage::Unexpected, Severity::Error),
+    }
+}
+
+// NOTE: For error codes of the Identity Toolkit API called without fars
+fn map_error_code_name(error_code: &str) -> (ErrorMessage, Severity) {
+    match error_code {
+        | "INVALID_LOGIN_CREDENTIALS" | "INVALID_PASSWORD" => {
+            (ErrorMessage::InvalidCredentials, Severity::Warning)
+        },
+        | "INVALID_EMAIL" => (ErrorMessage::InvalidEmail, Severity::Warning),
+        | "WEAK_PASSWORD" => (ErrorMessage::WeakPassword, Severity::Warning),
+        | "EMAIL_EXISTS" => (ErrorMessage::EmailExists, Severity::Warning),
+        | "EMAIL_NOT_FOUND" => {
+            (ErrorMessage::EmailNotFound, Severity::Warning)
+        },
+        | "USER_NOT_FOUND" => (ErrorMessage::UserNotFound, Severity::Error),
+        | "USER_DISABLED" => (ErrorMessage::UserDisabled, Severity::Error),
+        | "OPERATION_NOT_ALLOWED" => {
+            (ErrorMessage::OperationNotAllowed, Severity::Error)
+        },
+        | "TOO_MANY_ATTEMPTS_TRY_LATER" => {
+            (ErrorMessage::TooManyAttempts, Severity::Warning)
+        },
+        | "CREDENTIAL_TOO_OLD_LOGIN_AGAIN" => {
+            (ErrorMessage::CredentialTooOld, Severity::Info)
+        },
+        | "TOKEN_EXPIRED" | "INVALID_ID_TOKEN" | "INVALID_REFRESH_TOKEN" => {
+            (ErrorMessage::SessionExpired, Severity::Warning)
+        },
+        | "INVALID_OOB_CODE" => {
+            (ErrorMessage::InvalidActionCode, Severity::Warning)
+        },
+        | "EXPIRED_OOB_CODE" => {
+            (ErrorMessage::ExpiredActionCode, Severity::Warning)
+        },
+        | "FEDERATED_USER_ID_ALREADY_LINKED" => {
+            (ErrorMessage::CredentialAlreadyInUse, Severity::Warning)
+        },
+        | _ => (ErrorMessage::Unexpected, Severity::Error),
+    }
+}
+
+#[allow(non_snake_case)]
+#[component(no_case_check)]
+pub(crate) fn ErrorView(
+    cx: Scope,
+    error: ErrorPresentation,
+) -> Element {
+    // Setup hooks
+    let show_detail = use_state(cx, || false);
+    let toggle_label = match show_detail.get() {
+        | true => "Hide details",
+        | false => "Details",
+    };
+
+    render! {
+        div {
+            color: error.severity.color(),
+            label {
+                error.text(),
+            }
+
+            span {
+                onclick: move |_| show_detail.set(!show_detail.get()),
+                MatButton {
+                    label: toggle_label,
+                    outlined: true,
+                }
+            }
+
+            if *show_detail.get() {
+                render! {
+                    pre {
+                        color: "gray",
+                        error.detail.as_str(),
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,118 @@
+//! Calls to the Identity Toolkit API that are not covered by fars.
+//! See also [reference](https://firebase.google.com/docs/reference/rest/auth).
+pub(crate) mod action_code;
+pub(crate) mod change_email;
+pub(crate) mod change_password;
+pub(crate) mod email_link;
+pub(crate) mod error;
+pub(crate) mod idp;
+pub(crate) mod mfa;
+#[cfg(test)]
+mod mock_server;
+pub(crate) mod sign_up;
+
+use fars::{Config, Session};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{app_config, identity_toolkit::error::Error};
+
+/// A result type for the Identity Toolkit API.
+pub(crate) type Result<T> = std::result::Result<T, Error>;
+
+pub(crate) const DEFAULT_BASE_URL: &str =
+    "https://identitytoolkit.googleapis.com";
+
+// NOTE: Can be pointed to a local mock server or the Auth emulator
+// such as "http://localhost:9099/identitytoolkit.googleapis.com"
+fn base_url() -> &'static str {
+    &app_config::get().identity_toolkit_url
+}
+
+/// A session with its refresh token, which fars does not expose.
+pub(crate) struct TokenSession {
+    pub(crate) session: Session,
+    pub(crate) refresh_token: String,
+}
+
+/// Builds a fars session from a refresh token issued by an API call.
+async fn exchange_refresh_token(
+    config: &Config,
+    refresh_token: String,
+) -> std::result::Result<TokenSession, fars::error::Error> {
+    let session = config
+        .exchange_refresh_token(refresh_token.clone())
+        .await?;
+
+    Ok(TokenSession {
+        session,
+        refresh_token,
+    })
+}
+
+#[derive(Deserialize)]
+struct ErrorResponse {
+    error: ErrorResponseBody,
+}
+
+#[derive(Deserialize)]
+struct ErrorResponseBody {
+    message: String,
+}
+
+/// Posts a JSON request to the endpoint such as "v1/accounts:sendOobCode".
+async fn post<Request, Response>(
+    endpoint: &str,
+    request: &Request,
+) -> Result<Response>
+where
+    Request: Serialize,
+    Response: DeserializeOwned,
+{
+    let client = reqwest::Client::new();
+    let response = client
+        .post(format!(
+            "{}/{}?key={}",
+            base_url(),
+            endpoint,
+            app_config::get().firebase_api_key
+        ))
+        .json(request)
+        .send()
+        .await
+        .map_err(Error::HttpError)?;
+
+    let status_code = response.status();
+
+    let response_text = response
+        .text()
+        .await
+        .map_err(Error::ReadResponseFailed)?;
+
+    if status_code.is_success() {
+        serde_json::from_str::<Response>(&response_text).map_err(|error| {
+            Error::ResponseJsonError {
+                error,
+                json: response_text,
+            }
+        })
+    } else {
+        // NOTE: The message is like "WEAK_PASSWORD : Password should be ..."
+        let error_code = serde_json::from_str::<ErrorResponse>(&response_text)
+            .map(|response| {
+                response
+                    .error
+                    .message
+                    .split(" : ")
+                    .next()
+                    .unwrap_or_default()
+                    .to_string()
+            })
+            .unwrap_or_default();
+
+        Err(Error::ApiError {
+            status_code,
+            error_code,
+            response: response_text,
+        })
+    }
+}
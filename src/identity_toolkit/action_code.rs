@@ -0,0 +1,90 @@
+//! Out-of-band action codes sent by e-mail.
+//! See also [reference](https://firebase.google.com/docs/reference/rest/auth#section-verify-password-reset-code).
+use serde::{Deserialize, Serialize};
+
+use crate::identity_toolkit::{post, Result};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CheckActionCodeRequest {
+    oob_code: String,
+}
+
+/// Information of a valid action code.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ActionCodeInfo {
+    /// The e-mail address the code was sent to.
+    pub(crate) email: String,
+    /// Such as "PASSWORD_RESET".
+    pub(crate) request_type: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ConfirmPasswordResetRequest {
+    oob_code: String,
+    new_password: String,
+}
+
+#[derive(Deserialize)]
+struct ConfirmPasswordResetResponse {}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ApplyActionCodeRequest {
+    oob_code: String,
+}
+
+/// The account after an action code has been applied.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AppliedActionCode {
+    /// The user the code belongs to.
+    pub(crate) local_id: Option<String>,
+    /// The verified or restored e-mail address.
+    pub(crate) email: Option<String>,
+}
+
+/// Checks the action code without consuming it.
+pub(crate) async fn check_action_code(
+    oob_code: String
+) -> Result<ActionCodeInfo> {
+    post(
+        "v1/accounts:resetPassword",
+        &CheckActionCodeRequest {
+            oob_code,
+        },
+    )
+    .await
+}
+
+/// Sets the new password with a password reset code.
+pub(crate) async fn confirm_password_reset(
+    oob_code: String,
+    new_password: String,
+) -> Result<()> {
+    post::<_, ConfirmPasswordResetResponse>(
+        "v1/accounts:resetPassword",
+        &ConfirmPasswordResetRequest {
+            oob_code,
+            new_password,
+        },
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Applies an e-mail verification or e-mail recovery code.
+pub(crate) async fn apply_action_code(
+    oob_code: String
+) -> Result<AppliedActionCode> {
+    post(
+        "v1/accounts:update",
+        &ApplyActionCodeRequest {
+            oob_code,
+        },
+    )
+    .await
+}
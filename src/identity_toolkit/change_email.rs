@@ -0,0 +1,35 @@
+//! Changing the e-mail address after verifying the new one.
+//! See also [reference](https://cloud.google.com/identity-platform/docs/reference/rest/v1/accounts/sendOobCode).
+use serde::{Deserialize, Serialize};
+
+use crate::identity_toolkit::{post, Result};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct VerifyAndChangeEmailRequest {
+    request_type: &'static str,
+    id_token: String,
+    new_email: String,
+}
+
+#[derive(Deserialize)]
+struct SendOobCodeResponse {}
+
+/// Sends a verification link to the new address.
+/// The e-mail address is changed when the link is opened.
+pub(crate) async fn send_verify_and_change_email(
+    id_token: String,
+    new_email: String,
+) -> Result<()> {
+    post::<_, SendOobCodeResponse>(
+        "v1/accounts:sendOobCode",
+        &VerifyAndChangeEmailRequest {
+            request_type: "VERIFY_AND_CHANGE_EMAIL",
+            id_token,
+            new_email,
+        },
+    )
+    .await?;
+
+    Ok(())
+}
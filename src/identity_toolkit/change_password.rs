@@ -0,0 +1,91 @@
+//! Changing the password, which revokes the previous refresh tokens.
+//! See also [reference](https://firebase.google.com/docs/reference/rest/auth#section-change-password).
+use fars::Config;
+use serde::{Deserialize, Serialize};
+
+use crate::identity_toolkit::{
+    exchange_refresh_token, post, Result, TokenSession,
+};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ChangePasswordRequest {
+    id_token: String,
+    password: String,
+    return_secure_token: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ChangePasswordResponse {
+    refresh_token: String,
+}
+
+/// Changes the password and returns the refresh token that replaces the
+/// revoked ones.
+async fn update_password(
+    id_token: String,
+    password: String,
+) -> Result<String> {
+    let response = post::<_, ChangePasswordResponse>(
+        "v1/accounts:update",
+        &ChangePasswordRequest {
+            id_token,
+            password,
+            return_secure_token: true,
+        },
+    )
+    .await?;
+
+    Ok(response.refresh_token)
+}
+
+/// Changes the password and signs in again with the issued token.
+pub(crate) async fn change_password(
+    config: &Config,
+    id_token: String,
+    password: String,
+) -> anyhow::Result<TokenSession> {
+    let refresh_token = update_password(id_token, password).await?;
+    let session = exchange_refresh_token(config, refresh_token).await?;
+
+    Ok(session)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::identity_toolkit::{
+        error::Error,
+        mock_server::{self, block_on, ID_TOKEN, ROTATED_REFRESH_TOKEN},
+    };
+
+    #[test]
+    fn refresh_token_is_rotated() {
+        mock_server::setup();
+        let refresh_token = block_on(update_password(
+            ID_TOKEN.to_string(),
+            "new-password".to_string(),
+        ))
+        .unwrap();
+
+        assert_eq!(refresh_token, ROTATED_REFRESH_TOKEN);
+    }
+
+    #[test]
+    fn weak_password_is_rejected() {
+        mock_server::setup();
+        let result = block_on(update_password(
+            ID_TOKEN.to_string(),
+            "short".to_string(),
+        ));
+
+        match result {
+            | Err(Error::ApiError {
+                error_code,
+                ..
+            }) => assert_eq!(error_code, "WEAK_PASSWORD"),
+            | _ => panic!("Unexpected result: {:?}", result),
+        }
+    }
+}
@@ -0,0 +1,73 @@
+//! Passwordless sign-in with an e-mail link.
+//! See also [reference](https://firebase.google.com/docs/reference/rest/auth#section-send-email-link).
+use fars::Config;
+use serde::{Deserialize, Serialize};
+
+use crate::identity_toolkit::{
+    exchange_refresh_token, post, Result, TokenSession,
+};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SendSignInLinkRequest {
+    request_type: &'static str,
+    email: String,
+    continue_url: String,
+    can_handle_code_in_app: bool,
+}
+
+#[derive(Deserialize)]
+struct SendOobCodeResponse {}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SignInWithEmailLinkRequest {
+    email: String,
+    oob_code: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SignInWithEmailLinkResponse {
+    refresh_token: String,
+}
+
+/// Sends a sign-in link that opens the continue URL with an `oobCode`.
+pub(crate) async fn send_sign_in_link(
+    email: String,
+    continue_url: String,
+) -> Result<()> {
+    post::<_, SendOobCodeResponse>(
+        "v1/accounts:sendOobCode",
+        &SendSignInLinkRequest {
+            request_type: "EMAIL_SIGNIN",
+            email,
+            continue_url,
+            can_handle_code_in_app: true,
+        },
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Signs in with the `oobCode` of a sign-in link.
+pub(crate) async fn sign_in_with_email_link(
+    config: &Config,
+    email: String,
+    oob_code: String,
+) -> anyhow::Result<TokenSession> {
+    let response = post::<_, SignInWithEmailLinkResponse>(
+        "v1/accounts:signInWithEmailLink",
+        &SignInWithEmailLinkRequest {
+            email,
+            oob_code,
+        },
+    )
+    .await?;
+
+    // NOTE: Build a fars session from the issued refresh token
+    let session = exchange_refresh_token(config, response.refresh_token).await?;
+
+    Ok(session)
+}
@@ -0,0 +1,24 @@
+//! An error type for the Identity Toolkit API.
+
+/// An error type for the Identity Toolkit API.
+// NOTE: Variants are named after the errors of google-oauth-rs
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum Error {
+    #[error("HTTP error: {0:?}")]
+    HttpError(reqwest::Error),
+    #[error("Read response failed: {0:?}")]
+    ReadResponseFailed(reqwest::Error),
+    #[error("API error: ({status_code:?}) {error_code} {response:?}")]
+    ApiError {
+        status_code: reqwest::StatusCode,
+        // NOTE: Such as "INVALID_OOB_CODE"
+        error_code: String,
+        response: String,
+    },
+    #[error("Response JSON error: {error:?} - {json:?}")]
+    ResponseJsonError {
+        error: serde_json::Error,
+        json: String,
+    },
+}
@@ -0,0 +1,98 @@
+//! Signing in with a credential of an identity provider such as Google.
+//! See also [reference](https://firebase.google.com/docs/reference/rest/auth#section-sign-in-with-oauth-credential).
+use fars::Config;
+use serde::{Deserialize, Serialize};
+
+use crate::identity_toolkit::{
+    exchange_refresh_token, post, Result, TokenSession,
+};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SignInWithIdpRequest {
+    request_uri: String,
+    post_body: String,
+    return_secure_token: bool,
+    return_idp_credential: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SignInWithIdpResponse {
+    refresh_token: String,
+}
+
+/// Signs in with the OpenID Connect ID token of Google and returns the
+/// refresh token.
+async fn sign_in_with_google_id_token(
+    request_uri: String,
+    id_token: String,
+) -> Result<String> {
+    let response = post::<_, SignInWithIdpResponse>(
+        "v1/accounts:signInWithIdp",
+        &SignInWithIdpRequest {
+            request_uri,
+            post_body: format!(
+                "id_token={}&providerId=google.com",
+                id_token
+            ),
+            return_secure_token: true,
+            return_idp_credential: false,
+        },
+    )
+    .await?;
+
+    Ok(response.refresh_token)
+}
+
+/// Signs in with the ID token of Google, creating the account on the
+/// first sign-in.
+pub(crate) async fn sign_in_with_google(
+    config: &Config,
+    request_uri: String,
+    id_token: String,
+) -> anyhow::Result<TokenSession> {
+    let refresh_token =
+        sign_in_with_google_id_token(request_uri, id_token).await?;
+    let session = exchange_refresh_token(config, refresh_token).await?;
+
+    Ok(session)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::identity_toolkit::{
+        error::Error,
+        mock_server::{self, block_on, GOOGLE_ID_TOKEN},
+    };
+
+    #[test]
+    fn google_id_token_is_exchanged() {
+        mock_server::setup();
+        let refresh_token = block_on(sign_in_with_google_id_token(
+            "http://localhost:8080/auth/google-callback".to_string(),
+            GOOGLE_ID_TOKEN.to_string(),
+        ))
+        .unwrap();
+
+        assert_eq!(refresh_token, "google-refresh-token");
+    }
+
+    #[test]
+    fn invalid_google_id_token_is_rejected() {
+        mock_server::setup();
+        let result = block_on(sign_in_with_google_id_token(
+            "http://localhost:8080/auth/google-callback".to_string(),
+            "invalid".to_string(),
+        ));
+
+        match result {
+            | Err(Error::ApiError {
+                error_code,
+                ..
+            }) => assert_eq!(error_code, "INVALID_IDP_RESPONSE"),
+            | _ => panic!("Unexpected result: {:?}", result),
+        }
+    }
+}
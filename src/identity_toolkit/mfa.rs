@@ -0,0 +1,488 @@
+//! TOTP multi-factor authentication of Identity Platform.
+//! See also [reference](https://cloud.google.com/identity-platform/docs/reference/rest/v2/accounts.mfaEnrollment).
+use fars::Config;
+use serde::{Deserialize, Serialize};
+
+use crate::identity_toolkit::{
+    exchange_refresh_token, post, Result, TokenSession,
+};
+
+/// An enrolled second factor.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct MfaFactor {
+    pub(crate) mfa_enrollment_id: String,
+    #[serde(default)]
+    pub(crate) display_name: Option<String>,
+    #[serde(default)]
+    pub(crate) enrolled_at: Option<String>,
+}
+
+/// A started TOTP enrollment to be finalized with a verification code.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TotpSessionInfo {
+    /// The base32 encoded secret to register in an authenticator app.
+    pub(crate) shared_secret_key: String,
+    pub(crate) verification_code_length: u32,
+    pub(crate) hashing_algorithm: String,
+    pub(crate) period_sec: u32,
+    pub(crate) session_info: String,
+}
+
+/// The result of signing in with e-mail and password.
+pub(crate) enum PasswordSignIn {
+    Completed(TokenSession),
+    /// The second factor has to be verified to finish signing in.
+    MfaRequired(PendingMfaSignIn),
+}
+
+/// A sign-in waiting for the second factor.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct PendingMfaSignIn {
+    pub(crate) email: String,
+    pub(crate) pending_credential: String,
+    pub(crate) factors: Vec<MfaFactor>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SignInWithPasswordRequest {
+    email: String,
+    password: String,
+    return_secure_token: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SignInWithPasswordResponse {
+    refresh_token: Option<String>,
+    mfa_pending_credential: Option<String>,
+    #[serde(default)]
+    mfa_info: Vec<MfaFactor>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LookupRequest {
+    id_token: String,
+}
+
+#[derive(Deserialize)]
+struct LookupResponse {
+    #[serde(default)]
+    users: Vec<LookupUser>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LookupUser {
+    #[serde(default)]
+    mfa_info: Vec<MfaFactor>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StartEnrollmentRequest {
+    id_token: String,
+    totp_enrollment_info: TotpEnrollmentInfo,
+}
+
+#[derive(Serialize)]
+struct TotpEnrollmentInfo {}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StartEnrollmentResponse {
+    totp_session_info: TotpSessionInfo,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FinalizeEnrollmentRequest {
+    id_token: String,
+    display_name: String,
+    totp_verification_info: EnrollmentVerificationInfo,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct EnrollmentVerificationInfo {
+    session_info: String,
+    verification_code: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct WithdrawRequest {
+    id_token: String,
+    mfa_enrollment_id: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FinalizeSignInRequest {
+    mfa_pending_credential: String,
+    mfa_enrollment_id: String,
+    totp_verification_info: SignInVerificationInfo,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SignInVerificationInfo {
+    verification_code: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TokenResponse {
+    refresh_token: String,
+}
+
+/// Signs in with e-mail and password, telling when MFA is required.
+///
+/// NOTE: fars cannot read the MFA response of `signInWithPassword`.
+pub(crate) async fn sign_in_with_email_password(
+    config: &Config,
+    email: String,
+    password: String,
+) -> anyhow::Result<PasswordSignIn> {
+    let response = post::<_, SignInWithPasswordResponse>(
+        "v1/accounts:signInWithPassword",
+        &SignInWithPasswordRequest {
+            email: email.clone(),
+            password,
+            return_secure_token: true,
+        },
+    )
+    .await?;
+
+    match (
+        response.mfa_pending_credential,
+        response.refresh_token,
+    ) {
+        | (Some(pending_credential), _) => {
+            Ok(PasswordSignIn::MfaRequired(PendingMfaSignIn {
+                email,
+                pending_credential,
+                factors: response.mfa_info,
+            }))
+        },
+        | (None, Some(refresh_token)) => {
+            let session = exchange_refresh_token(config, refresh_token).await?;
+            Ok(PasswordSignIn::Completed(session))
+        },
+        | (None, None) => Err(anyhow::anyhow!(
+            "Neither a token nor an MFA credential is returned"
+        )),
+    }
+}
+
+/// Verifies the TOTP code of the pending sign-in.
+pub(crate) async fn finalize_sign_in(
+    config: &Config,
+    pending: &PendingMfaSignIn,
+    mfa_enrollment_id: String,
+    verification_code: String,
+) -> anyhow::Result<TokenSession> {
+    let response = post::<_, TokenResponse>(
+        "v2/accounts/mfaSignIn:finalize",
+        &FinalizeSignInRequest {
+            mfa_pending_credential: pending.pending_credential.clone(),
+            mfa_enrollment_id,
+            totp_verification_info: SignInVerificationInfo {
+                verification_code,
+            },
+        },
+    )
+    .await?;
+
+    let session = exchange_refresh_token(config, response.refresh_token).await?;
+
+    Ok(session)
+}
+
+/// Lists the enrolled second factors of the user.
+pub(crate) async fn list_factors(id_token: String) -> Result<Vec<MfaFactor>> {
+    let response = post::<_, LookupResponse>(
+        "v1/accounts:lookup",
+        &LookupRequest {
+            id_token,
+        },
+    )
+    .await?;
+
+    Ok(response
+        .users
+        .into_iter()
+        .next()
+        .map(|user| user.mfa_info)
+        .unwrap_or_default())
+}
+
+/// Generates a TOTP secret for the user.
+pub(crate) async fn start_enrollment(
+    id_token: String
+) -> Result<TotpSessionInfo> {
+    let response = post::<_, StartEnrollmentResponse>(
+        "v2/accounts/mfaEnrollment:start",
+        &StartEnrollmentRequest {
+            id_token,
+            totp_enrollment_info: TotpEnrollmentInfo {},
+        },
+    )
+    .await?;
+
+    Ok(response.totp_session_info)
+}
+
+/// Enrolls the TOTP factor and returns the refresh token that replaces
+/// the revoked ones.
+async fn enroll(
+    id_token: String,
+    session_info: String,
+    display_name: String,
+    verification_code: String,
+) -> Result<String> {
+    let response = post::<_, TokenResponse>(
+        "v2/accounts/mfaEnrollment:finalize",
+        &FinalizeEnrollmentRequest {
+            id_token,
+            display_name,
+            totp_verification_info: EnrollmentVerificationInfo {
+                session_info,
+                verification_code,
+            },
+        },
+    )
+    .await?;
+
+    Ok(response.refresh_token)
+}
+
+/// Enrolls the TOTP factor with a code from the authenticator app.
+pub(crate) async fn finalize_enrollment(
+    config: &Config,
+    id_token: String,
+    session_info: String,
+    display_name: String,
+    verification_code: String,
+) -> anyhow::Result<TokenSession> {
+    let refresh_token =
+        enroll(id_token, session_info, display_name, verification_code)
+            .await?;
+
+    // NOTE: Enrolling revokes the previous tokens
+    let session = exchange_refresh_token(config, refresh_token).await?;
+
+    Ok(session)
+}
+
+/// Removes the enrolled factor and returns the refresh token that
+/// replaces the revoked ones.
+async fn withdraw_factor(
+    id_token: String,
+    mfa_enrollment_id: String,
+) -> Result<String> {
+    let response = post::<_, TokenResponse>(
+        "v2/accounts/mfaEnrollment:withdraw",
+        &WithdrawRequest {
+            id_token,
+            mfa_enrollment_id,
+        },
+    )
+    .await?;
+
+    Ok(response.refresh_token)
+}
+
+/// Removes the enrolled factor.
+pub(crate) async fn withdraw(
+    config: &Config,
+    id_token: String,
+    mfa_enrollment_id: String,
+) -> anyhow::Result<TokenSession> {
+    let refresh_token = withdraw_factor(id_token, mfa_enrollment_id).await?;
+    let session = exchange_refresh_token(config, refresh_token).await?;
+
+    Ok(session)
+}
+
+/// Builds an `otpauth://` URI to register the secret in an authenticator
+/// app.
+pub(crate) fn totp_uri(
+    info: &TotpSessionInfo,
+    issuer: &str,
+    account_name: &str,
+) -> String {
+    let mut url = match reqwest::Url::parse("otpauth://totp/") {
+        | Ok(url) => url,
+        | Err(_) => return String::new(),
+    };
+    url.set_path(&format!("{}:{}", issuer, account_name));
+    url.query_pairs_mut()
+        .append_pair("secret", &info.shared_secret_key)
+        .append_pair("issuer", issuer)
+        .append_pair("algorithm", &info.hashing_algorithm)
+        .append_pair(
+            "digits",
+            &info
+                .verification_code_length
+                .to_string(),
+        )
+        .append_pair("period", &info.period_sec.to_string());
+
+    url.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use fars::Config;
+
+    use super::*;
+    use crate::identity_toolkit::{
+        error::Error,
+        mock_server::{
+            self, block_on, API_KEY, ID_TOKEN, MFA_EMAIL,
+            ROTATED_REFRESH_TOKEN, VERIFICATION_CODE,
+        },
+    };
+
+    #[test]
+    fn sign_in_with_second_factor_is_pending() {
+        mock_server::setup();
+        let result = block_on(sign_in_with_email_password(
+            &Config::new(API_KEY.to_string()),
+            MFA_EMAIL.to_string(),
+            "password".to_string(),
+        ));
+
+        match result {
+            | Ok(PasswordSignIn::MfaRequired(pending)) => {
+                assert_eq!(pending.email, MFA_EMAIL);
+                assert_eq!(pending.pending_credential, "pending-credential");
+                assert_eq!(pending.factors.len(), 1);
+                assert_eq!(pending.factors[0].mfa_enrollment_id, "factor-1");
+            },
+            | Ok(PasswordSignIn::Completed(_)) => {
+                panic!("Sign-in must wait for the second factor")
+            },
+            | Err(error) => panic!("Sign-in failed: {:?}", error),
+        }
+    }
+
+    #[test]
+    fn sign_in_error_code_is_parsed() {
+        mock_server::setup();
+        let result = block_on(sign_in_with_email_password(
+            &Config::new(API_KEY.to_string()),
+            "user@example.com".to_string(),
+            "wrong".to_string(),
+        ));
+
+        let error = match result {
+            | Err(error) => error,
+            | Ok(_) => panic!("Sign-in must fail"),
+        };
+        match error.downcast_ref::<Error>() {
+            | Some(Error::ApiError {
+                status_code,
+                error_code,
+                ..
+            }) => {
+                assert_eq!(status_code.as_u16(), 400);
+                assert_eq!(error_code, "INVALID_LOGIN_CREDENTIALS");
+            },
+            | _ => panic!("Unexpected error: {:?}", error),
+        }
+    }
+
+    #[test]
+    fn factors_are_listed() {
+        mock_server::setup();
+        let factors = block_on(list_factors(ID_TOKEN.to_string())).unwrap();
+
+        assert_eq!(
+            factors,
+            vec![MfaFactor {
+                mfa_enrollment_id: "factor-1".to_string(),
+                display_name: Some("Phone".to_string()),
+                enrolled_at: Some("2024-01-01T00:00:00Z".to_string()),
+            }]
+        );
+    }
+
+    #[test]
+    fn enrollment_is_started() {
+        mock_server::setup();
+        let info = block_on(start_enrollment(ID_TOKEN.to_string())).unwrap();
+
+        assert_eq!(info.shared_secret_key, "JBSWY3DPEHPK3PXP");
+        assert_eq!(info.verification_code_length, 6);
+        assert_eq!(info.session_info, "session-info");
+        assert!(totp_uri(&info, "Issuer", "user@example.com")
+            .starts_with("otpauth://totp/Issuer:user@example.com?secret="));
+    }
+
+    #[test]
+    fn enrollment_is_finalized() {
+        mock_server::setup();
+        let refresh_token = block_on(enroll(
+            ID_TOKEN.to_string(),
+            "session-info".to_string(),
+            "Authenticator app".to_string(),
+            VERIFICATION_CODE.to_string(),
+        ))
+        .unwrap();
+
+        assert_eq!(refresh_token, ROTATED_REFRESH_TOKEN);
+    }
+
+    #[test]
+    fn wrong_enrollment_code_is_rejected() {
+        mock_server::setup();
+        let result = block_on(enroll(
+            ID_TOKEN.to_string(),
+            "session-info".to_string(),
+            "Authenticator app".to_string(),
+            "000000".to_string(),
+        ));
+
+        match result {
+            | Err(Error::ApiError {
+                error_code,
+                ..
+            }) => assert_eq!(error_code, "INVALID_CODE"),
+            | _ => panic!("Unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn factor_is_withdrawn() {
+        mock_server::setup();
+        let refresh_token = block_on(withdraw_factor(
+            ID_TOKEN.to_string(),
+            "factor-1".to_string(),
+        ))
+        .unwrap();
+
+        assert_eq!(refresh_token, ROTATED_REFRESH_TOKEN);
+    }
+
+    #[test]
+    fn unknown_factor_is_not_withdrawn() {
+        mock_server::setup();
+        let result = block_on(withdraw_factor(
+            ID_TOKEN.to_string(),
+            "factor-2".to_string(),
+        ));
+
+        match result {
+            | Err(Error::ApiError {
+                error_code,
+                ..
+            }) => assert_eq!(error_code, "MFA_ENROLLMENT_NOT_FOUND"),
+            | _ => panic!("Unexpected result: {:?}", result),
+        }
+    }
+}
@@ -0,0 +1,231 @@
+//! A local server that answers canned Identity Toolkit responses to test
+//! the calls of this crate.
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    sync::Once,
+};
+
+use serde_json::{json, Value};
+
+use crate::app_config::{self, AppConfig, Provider};
+
+pub(crate) const API_KEY: &str = "test-api-key";
+pub(crate) const ID_TOKEN: &str = "test-id-token";
+pub(crate) const MFA_EMAIL: &str = "mfa@example.com";
+pub(crate) const EXISTING_EMAIL: &str = "existing@example.com";
+pub(crate) const GOOGLE_ID_TOKEN: &str = "google-id-token";
+pub(crate) const ROTATED_REFRESH_TOKEN: &str = "rotated-refresh-token";
+pub(crate) const VERIFICATION_CODE: &str = "123456";
+
+/// Starts the server once and points the app config to it.
+pub(crate) fn setup() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        app_config::install(AppConfig {
+            firebase_api_key: API_KEY.to_string(),
+            google_client_id: String::new(),
+            redirect_origin: None,
+            enabled_providers: vec![Provider::Password],
+            log_level: log::LevelFilter::Off,
+            identity_toolkit_url: start(),
+        });
+    });
+}
+
+pub(crate) fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(future)
+}
+
+fn start() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            respond(stream);
+        }
+    });
+
+    url
+}
+
+fn respond(mut stream: TcpStream) {
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    let mut request_line = String::new();
+    reader
+        .read_line(&mut request_line)
+        .unwrap();
+
+    let mut content_length = 0;
+    loop {
+        let mut header = String::new();
+        reader.read_line(&mut header).unwrap();
+        if header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap();
+            }
+        }
+    }
+
+    let mut body = vec![0_u8; content_length];
+    reader.read_exact(&mut body).unwrap();
+    let body = serde_json::from_slice::<Value>(&body).unwrap_or(Value::Null);
+
+    let target = request_line
+        .split_whitespace()
+        .nth(1)
+        .unwrap_or_default();
+    let (path, query) = target
+        .split_once('?')
+        .unwrap_or((target, ""));
+
+    let (status, response) = if query != format!("key={}", API_KEY) {
+        ("403 Forbidden", error_response("API_KEY_INVALID"))
+    } else {
+        route(path, &body)
+    };
+    let response = response.to_string();
+
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        response.len(),
+        response
+    )
+    .unwrap();
+}
+
+fn route(
+    path: &str,
+    body: &Value,
+) -> (&'static str, Value) {
+    let has_id_token = body["idToken"] == ID_TOKEN;
+
+    match path {
+        | "/v1/accounts:signInWithPassword" if body["email"] == MFA_EMAIL => (
+            "200 OK",
+            json!({
+                "mfaPendingCredential": "pending-credential",
+                "mfaInfo": [{
+                    "mfaEnrollmentId": "factor-1",
+                    "displayName": "Phone",
+                }],
+            }),
+        ),
+        | "/v1/accounts:signInWithPassword" => (
+            "400 Bad Request",
+            error_response("INVALID_LOGIN_CREDENTIALS"),
+        ),
+        | "/v1/accounts:signUp" if body["email"] == EXISTING_EMAIL => {
+            ("400 Bad Request", error_response("EMAIL_EXISTS"))
+        },
+        | "/v1/accounts:signUp" if body["password"].is_string() => (
+            "200 OK",
+            json!({ "refreshToken": "password-refresh-token" }),
+        ),
+        | "/v1/accounts:signUp" => (
+            "200 OK",
+            json!({ "refreshToken": "anonymous-refresh-token" }),
+        ),
+        | "/v1/accounts:signInWithIdp" if is_google_credential(body) => (
+            "200 OK",
+            json!({ "refreshToken": "google-refresh-token" }),
+        ),
+        | "/v1/accounts:signInWithIdp" => {
+            ("400 Bad Request", error_response("INVALID_IDP_RESPONSE"))
+        },
+        | _ if !has_id_token => {
+            ("400 Bad Request", error_response("INVALID_ID_TOKEN"))
+        },
+        | "/v1/accounts:lookup" => (
+            "200 OK",
+            json!({
+                "users": [{
+                    "mfaInfo": [{
+                        "mfaEnrollmentId": "factor-1",
+                        "displayName": "Phone",
+                        "enrolledAt": "2024-01-01T00:00:00Z",
+                    }],
+                }],
+            }),
+        ),
+        | "/v1/accounts:update" if body["password"].is_string() => {
+            if body["password"]
+                .as_str()
+                .unwrap_or_default()
+                .len()
+                < 6
+            {
+                ("400 Bad Request", error_response("WEAK_PASSWORD"))
+            } else {
+                ("200 OK", token_response())
+            }
+        },
+        | "/v2/accounts/mfaEnrollment:start" => (
+            "200 OK",
+            json!({
+                "totpSessionInfo": {
+                    "sharedSecretKey": "JBSWY3DPEHPK3PXP",
+                    "verificationCodeLength": 6,
+                    "hashingAlgorithm": "SHA1",
+                    "periodSec": 30,
+                    "sessionInfo": "session-info",
+                },
+            }),
+        ),
+        | "/v2/accounts/mfaEnrollment:finalize" => {
+            let info = &body["totpVerificationInfo"];
+            if info["sessionInfo"] == "session-info"
+                && info["verificationCode"] == VERIFICATION_CODE
+            {
+                ("200 OK", token_response())
+            } else {
+                ("400 Bad Request", error_response("INVALID_CODE"))
+            }
+        },
+        | "/v2/accounts/mfaEnrollment:withdraw" => {
+            if body["mfaEnrollmentId"] == "factor-1" {
+                ("200 OK", token_response())
+            } else {
+                (
+                    "400 Bad Request",
+                    error_response("MFA_ENROLLMENT_NOT_FOUND"),
+                )
+            }
+        },
+        | _ => ("404 Not Found", error_response("NOT_FOUND")),
+    }
+}
+
+fn is_google_credential(body: &Value) -> bool {
+    let post_body =
+        format!("id_token={}&providerId=google.com", GOOGLE_ID_TOKEN);
+
+    body["requestUri"].is_string() && body["postBody"] == post_body
+}
+
+// NOTE: These calls revoke the previous tokens and issue new ones
+fn token_response() -> Value {
+    json!({
+        "idToken": "rotated-id-token",
+        "refreshToken": ROTATED_REFRESH_TOKEN,
+    })
+}
+
+fn error_response(message: &str) -> Value {
+    json!({
+        "error": {
+            "code": 400,
+            "message": format!("{} : Mocked error", message),
+        },
+    })
+}
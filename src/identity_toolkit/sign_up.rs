@@ -0,0 +1,111 @@
+//! Creating an account with e-mail and password or anonymously.
+//! See also [reference](https://firebase.google.com/docs/reference/rest/auth#section-create-email-password).
+use fars::Config;
+use serde::{Deserialize, Serialize};
+
+use crate::identity_toolkit::{
+    exchange_refresh_token, post, Result, TokenSession,
+};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SignUpRequest {
+    // NOTE: An anonymous account is created without them
+    #[serde(skip_serializing_if = "Option::is_none")]
+    email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    password: Option<String>,
+    return_secure_token: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SignUpResponse {
+    refresh_token: String,
+}
+
+/// Creates an account and returns its refresh token.
+async fn sign_up(
+    email: Option<String>,
+    password: Option<String>,
+) -> Result<String> {
+    let response = post::<_, SignUpResponse>(
+        "v1/accounts:signUp",
+        &SignUpRequest {
+            email,
+            password,
+            return_secure_token: true,
+        },
+    )
+    .await?;
+
+    Ok(response.refresh_token)
+}
+
+/// Creates an account with e-mail and password and signs in to it.
+pub(crate) async fn sign_up_with_email_password(
+    config: &Config,
+    email: String,
+    password: String,
+) -> anyhow::Result<TokenSession> {
+    let refresh_token = sign_up(Some(email), Some(password)).await?;
+    let session = exchange_refresh_token(config, refresh_token).await?;
+
+    Ok(session)
+}
+
+/// Creates an anonymous account and signs in to it.
+pub(crate) async fn sign_in_anonymously(
+    config: &Config
+) -> anyhow::Result<TokenSession> {
+    let refresh_token = sign_up(None, None).await?;
+    let session = exchange_refresh_token(config, refresh_token).await?;
+
+    Ok(session)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::identity_toolkit::{
+        error::Error,
+        mock_server::{self, block_on, EXISTING_EMAIL},
+    };
+
+    #[test]
+    fn account_is_created_with_password() {
+        mock_server::setup();
+        let refresh_token = block_on(sign_up(
+            Some("new@example.com".to_string()),
+            Some("password".to_string()),
+        ))
+        .unwrap();
+
+        assert_eq!(refresh_token, "password-refresh-token");
+    }
+
+    #[test]
+    fn anonymous_account_is_created() {
+        mock_server::setup();
+        let refresh_token = block_on(sign_up(None, None)).unwrap();
+
+        assert_eq!(refresh_token, "anonymous-refresh-token");
+    }
+
+    #[test]
+    fn existing_email_is_rejected() {
+        mock_server::setup();
+        let result = block_on(sign_up(
+            Some(EXISTING_EMAIL.to_string()),
+            Some("password".to_string()),
+        ));
+
+        match result {
+            | Err(Error::ApiError {
+                error_code,
+                ..
+            }) => assert_eq!(error_code, "EMAIL_EXISTS"),
+            | _ => panic!("Unexpected result: {:?}", result),
+        }
+    }
+}
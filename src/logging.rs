@@ -1,7 +1,13 @@
 use log::LevelFilter;
 
 pub(crate) fn initialize() -> anyhow::Result<()> {
-    dioxus_logger::init(LevelFilter::Info)?;
+    // NOTE: Narrowed by the max level until the app config is loaded
+    dioxus_logger::init(LevelFilter::Trace)?;
+    log::set_max_level(LevelFilter::Info);
 
     Ok(())
 }
+
+pub(crate) fn set_level(level: LevelFilter) {
+    log::set_max_level(level);
+}
@@ -1,24 +1,43 @@
+mod app_config;
 mod application_context;
+mod async_action;
+mod auth;
 mod credential;
+// NOTE: Used by build.rs, compiled into the crate only to be tested
+#[cfg(test)]
+mod dotenv_parser;
+mod email_hint;
+mod error_presentation;
 mod generated;
+mod identity_toolkit;
 mod logging;
+mod mfa_settings;
+mod notification;
+mod one_shot;
+mod password_checklist;
+mod profile_banner;
+mod reauthentication;
 mod routings;
+mod storage;
 mod style;
-
-use async_std::sync::Mutex;
-use std::sync::Arc;
+mod throttle;
 
 use dioxus::{
-    hooks::use_shared_state_provider,
+    hooks::{use_future, use_shared_state_provider},
     prelude::{
         dioxus_elements, fc_to_builder, render, Element, GlobalAttributes,
-        Scope,
+        IntoDynNode, Scope,
     },
 };
 use dioxus_router::prelude::Router;
 use material_dioxus::MatTheme;
 
-use crate::application_context::ApplicationContext;
+use crate::{
+    application_context::ApplicationContext,
+    auth::{restore_accounts, use_auth},
+    notification::{NotificationHost, NotificationQueue},
+    profile_banner::ProfileBanner,
+};
 
 fn main() -> anyhow::Result<()> {
     logging::initialize()?;
@@ -29,8 +48,16 @@ fn main() -> anyhow::Result<()> {
 }
 
 fn app(cx: Scope) -> Element {
-    use_shared_state_provider::<Arc<Mutex<ApplicationContext>>>(cx, || {
-        Arc::new(Mutex::new(ApplicationContext::default()))
+    let config = use_future(cx, (), |_| async {
+        let config = app_config::load().await;
+        match &config {
+            | Ok(config) => {
+                logging::set_level(config.log_level);
+                app_config::install(config.clone());
+            },
+            | Err(error) => log::error!("Load app config failed: {}", error),
+        }
+        config.map_err(|error| error.to_string())
     });
 
     render! {
@@ -41,6 +68,44 @@ fn app(cx: Scope) -> Element {
 
         MatTheme { }
 
+        ProfileBanner { }
+
+        match config.value() {
+            | None => render! {
+                div {
+                    "Loading..."
+                }
+            },
+            | Some(Err(error)) => render! {
+                h1 { "Invalid configuration" }
+
+                div {
+                    color: "red",
+                    label {
+                        "{error}"
+                    }
+                }
+            },
+            | Some(Ok(_)) => render! {
+                LoadedApp { }
+            },
+        }
+    }
+}
+
+// NOTE: The auth context needs the loaded config
+#[allow(non_snake_case)]
+fn LoadedApp(cx: Scope) -> Element {
+    use_shared_state_provider(cx, ApplicationContext::default);
+    use_shared_state_provider(cx, NotificationQueue::default);
+    let auth = use_auth(cx);
+
+    // NOTE: Sign in again the accounts of the previous visit
+    use_future(cx, (), |_| restore_accounts(auth.clone()));
+
+    render! {
         Router::<crate::routings::route::Route> {}
+
+        NotificationHost { }
     }
 }
@@ -0,0 +1,313 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use dioxus::prelude::{
+    component, dioxus_elements, fc_to_builder, render, to_owned, use_future,
+    use_state, Element, GlobalAttributes, IntoDynNode, Props, Scope, ScopeState,
+    UseState,
+};
+use material_dioxus::{button::MatButton, text_inputs::MatTextField};
+use qrcode::{render::svg, QrCode};
+
+use crate::{
+    async_action::{use_async_action, ActionButton, UseAsyncAction},
+    auth::{use_auth, UseAuth},
+    error_presentation::{ErrorPresentation, ErrorView},
+    identity_toolkit::mfa::{
+        finalize_enrollment, list_factors, start_enrollment, totp_uri,
+        MfaFactor, TotpSessionInfo,
+    },
+    notification::{use_notifications, UseNotifications},
+    reauthentication::{SensitiveOperation, UseSensitiveOperation},
+};
+
+const ISSUER: &str = "Frontend Playground";
+
+type Factors = Result<Vec<MfaFactor>, ErrorPresentation>;
+
+#[allow(non_snake_case)]
+#[component(no_case_check)]
+pub(crate) fn MfaSettings<'a>(
+    cx: Scope<'a>,
+    operation: &'a UseSensitiveOperation,
+    // NOTE: Not stripped by props, because None is passed explicitly
+    #[props(!optional)]
+    email: Option<String>,
+) -> Element<'a> {
+    // Setup hooks
+    let auth = use_auth(cx);
+    let notifications = use_notifications(cx);
+    let enrollment = use_state::<Option<TotpSessionInfo>>(cx, || None);
+    let display_name = use_state(cx, || "Authenticator app".to_string());
+    let verification_code = use_state(cx, String::new);
+    let enrollment_action = use_async_action(cx);
+    // NOTE: Incremented to reload the factors after a change
+    let factors_revision = use_state(cx, || 0_u64);
+
+    let active_account_id = auth.active_account_id();
+    let removed_revision = operation.factors_revision();
+
+    let factors = use_future(
+        cx,
+        (factors_revision.get(), &removed_revision, &active_account_id),
+        |_| {
+            let auth = auth.clone();
+            async move { fetch_factors(&auth).await }
+        },
+    );
+
+    render! {
+        div {
+            outline: "1px solid green",
+            padding: "10px",
+
+            h2 { "Two-step verification" }
+
+            match factors.value() {
+                | None => render! {
+                    div {
+                        "Loading second factors..."
+                    }
+                },
+                | Some(Err(error)) => render! {
+                    ErrorView {
+                        error: error.clone(),
+                    }
+                },
+                | Some(Ok(factors_value)) if factors_value.is_empty() => render! {
+                    div {
+                        "No second factor is enrolled."
+                    }
+                },
+                | Some(Ok(factors_value)) => render! {
+                    for factor in factors_value.iter() {
+                        render! {
+                            div {
+                                label {
+                                    factor.display_name.clone().unwrap_or("Authenticator app".to_string())
+                                }
+
+                                label {
+                                    " (enrolled at {factor.enrolled_at.clone().unwrap_or_default()}) "
+                                }
+
+                                ActionButton {
+                                    label: "Remove",
+                                    action: operation.action(),
+                                    onclick: move |_| operation.spawn(cx, SensitiveOperation::RemoveSecondFactor {
+                                        mfa_enrollment_id: factor.mfa_enrollment_id.clone(),
+                                    }),
+                                }
+                            }
+                        }
+                    }
+                },
+            }
+
+            br {}
+
+            match enrollment.get() {
+                | None => render! {
+                    div {
+                        ActionButton {
+                            label: "Set up authenticator app",
+                            action: enrollment_action,
+                            onclick: |_| begin_enrollment(cx, auth, notifications, enrollment_action, enrollment),
+                        }
+                    }
+                },
+                | Some(info) => render! {
+                    div {
+                        label {
+                            "Scan the QR code with your authenticator app, or enter the secret manually."
+                        }
+                    }
+
+                    if let Some(data_uri) = qr_code_data_uri(&totp_uri(info, ISSUER, email.as_deref().unwrap_or_default())) {
+                        render! {
+                            div {
+                                img {
+                                    src: "{data_uri}",
+                                    alt: "QR code",
+                                    width: "200",
+                                    height: "200",
+                                }
+                            }
+                        }
+                    }
+
+                    div {
+                        "Secret: "
+                        code {
+                            info.shared_secret_key.clone()
+                        }
+                    }
+
+                    div {
+                        MatTextField {
+                            label: "Name",
+                            value: display_name.get(),
+                            _oninput: {
+                                to_owned![display_name];
+                                move |event: String| {
+                                    display_name.set(event)
+                                }
+                            }
+                        }
+                    }
+
+                    div {
+                        MatTextField {
+                            label: "Verification code",
+                            value: verification_code.get(),
+                            _oninput: {
+                                to_owned![verification_code];
+                                move |event: String| {
+                                    verification_code.set(event)
+                                }
+                            }
+                        }
+                    }
+
+                    div {
+                        ActionButton {
+                            label: "Verify and enable",
+                            action: enrollment_action,
+                            disabled: verification_code.get().trim().is_empty() || display_name.get().is_empty(),
+                            onclick: move |_| complete_enrollment(cx, auth, notifications, enrollment_action, factors_revision, enrollment, info.session_info.clone(), display_name.get().clone(), verification_code),
+                        }
+
+                        span {
+                            onclick: |_| enrollment.set(None),
+                            MatButton {
+                                label: "Cancel",
+                                outlined: true,
+                                disabled: enrollment_action.is_pending(),
+                            }
+                        }
+                    }
+                },
+            }
+        }
+    }
+}
+
+async fn fetch_factors(auth: &UseAuth) -> Factors {
+    let snapshot = match auth.snapshot() {
+        | Some(snapshot) => snapshot,
+        | None => return Ok(Vec::new()),
+    };
+
+    log::info!("List second factors");
+    match list_factors(snapshot.session.id_token.clone()).await {
+        | Ok(factors) => {
+            log::info!("List second factors success");
+            Ok(factors)
+        },
+        | Err(error) => {
+            log::error!("List second factors failed: {:?}", error);
+            Err((&error).into())
+        },
+    }
+}
+
+fn qr_code_data_uri(text: &str) -> Option<String> {
+    let code = QrCode::new(text.as_bytes()).ok()?;
+    let image = code
+        .render::<svg::Color>()
+        .min_dimensions(200, 200)
+        .build();
+
+    Some(format!(
+        "data:image/svg+xml;base64,{}",
+        STANDARD.encode(image)
+    ))
+}
+
+fn begin_enrollment(
+    cx: &ScopeState,
+    auth: &UseAuth,
+    notifications: &UseNotifications,
+    action: &UseAsyncAction,
+    enrollment: &UseState<Option<TotpSessionInfo>>,
+) {
+    // Setup hooks
+    let auth = auth.clone();
+    let notifications = notifications.clone();
+    let enrollment = enrollment.clone();
+
+    action.run(cx, async move {
+        let snapshot = match auth.snapshot() {
+            | Some(snapshot) => snapshot,
+            | None => return Ok(()),
+        };
+
+        log::info!("Start TOTP enrollment");
+        match start_enrollment(snapshot.session.id_token.clone()).await {
+            | Ok(info) => {
+                log::info!("Start TOTP enrollment success");
+                enrollment.set(Some(info));
+                Ok(())
+            },
+            | Err(error) => {
+                log::error!("Start TOTP enrollment failed: {:?}", error);
+                let error = ErrorPresentation::from(&error);
+                notifications.error(error.clone());
+                Err(error)
+            },
+        }
+    });
+}
+
+// NOTE: Takes the hooks of the settings
+#[allow(clippy::too_many_arguments)]
+fn complete_enrollment(
+    cx: &ScopeState,
+    auth: &UseAuth,
+    notifications: &UseNotifications,
+    action: &UseAsyncAction,
+    factors_revision: &UseState<u64>,
+    enrollment: &UseState<Option<TotpSessionInfo>>,
+    session_info: String,
+    display_name: String,
+    verification_code: &UseState<String>,
+) {
+    // Setup hooks
+    let auth = auth.clone();
+    let notifications = notifications.clone();
+    let factors_revision = factors_revision.clone();
+    let enrollment = enrollment.clone();
+    let verification_code = verification_code.clone();
+
+    action.run(cx, async move {
+        let snapshot = match auth.snapshot() {
+            | Some(snapshot) => snapshot,
+            | None => return Ok(()),
+        };
+
+        log::info!("Finalize TOTP enrollment");
+        match finalize_enrollment(
+            &auth.config(),
+            snapshot.session.id_token.clone(),
+            session_info,
+            display_name,
+            verification_code.current().trim().to_string(),
+        )
+        .await
+        {
+            | Ok(new_session) => {
+                log::info!("Finalize TOTP enrollment success");
+                auth.update_token_session(&snapshot, new_session);
+                notifications.success("Two-step verification has been enabled.");
+                enrollment.set(None);
+                verification_code.set(String::new());
+                factors_revision.modify(|revision| revision + 1);
+                Ok(())
+            },
+            | Err(error) => {
+                log::error!("Finalize TOTP enrollment failed: {:?}", error);
+                let error = ErrorPresentation::from(&error);
+                notifications.error(error.clone());
+                Err(error)
+            },
+        }
+    });
+}
@@ -0,0 +1,214 @@
+use std::time::Duration;
+
+use dioxus::prelude::{
+    component, dioxus_elements, fc_to_builder, render, use_future,
+    use_shared_state, Element, GlobalAttributes, IntoDynNode, Props, Scope,
+    ScopeState, UseSharedState,
+};
+use material_dioxus::MatButton;
+
+use crate::error_presentation::{ErrorPresentation, ErrorView, Severity};
+
+const MAX_VISIBLE_NOTIFICATIONS: usize = 4;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum NotificationKind {
+    Success,
+    Info,
+    Error,
+}
+
+impl NotificationKind {
+    fn color(&self) -> &'static str {
+        match self {
+            | NotificationKind::Success => "green",
+            | NotificationKind::Info => "gray",
+            | NotificationKind::Error => "red",
+        }
+    }
+
+    fn duration(&self) -> Duration {
+        match self {
+            | NotificationKind::Success => Duration::from_secs(4),
+            | NotificationKind::Info => Duration::from_secs(4),
+            | NotificationKind::Error => Duration::from_secs(8),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Notification {
+    id: u64,
+    kind: NotificationKind,
+    message: String,
+    error: Option<ErrorPresentation>,
+}
+
+#[derive(Default)]
+pub(crate) struct NotificationQueue {
+    next_id: u64,
+    notifications: Vec<Notification>,
+}
+
+/// A handle of the app-wide notification queue.
+///
+/// It can be cloned into spawned tasks and outlives the route
+/// that pushed a notification.
+#[derive(Clone)]
+pub(crate) struct UseNotifications {
+    queue: UseSharedState<NotificationQueue>,
+}
+
+pub(crate) fn use_notifications(cx: &ScopeState) -> &UseNotifications {
+    let queue = use_shared_state::<NotificationQueue>(cx)
+        .expect("NotificationQueue is not provided")
+        .clone();
+
+    cx.use_hook(|| UseNotifications {
+        queue,
+    })
+}
+
+impl UseNotifications {
+    pub(crate) fn success<S: Into<String>>(
+        &self,
+        message: S,
+    ) {
+        self.push(NotificationKind::Success, message.into(), None);
+    }
+
+    pub(crate) fn info<S: Into<String>>(
+        &self,
+        message: S,
+    ) {
+        self.push(NotificationKind::Info, message.into(), None);
+    }
+
+    pub(crate) fn error(
+        &self,
+        error: ErrorPresentation,
+    ) {
+        let kind = match error.severity {
+            | Severity::Info => NotificationKind::Info,
+            | Severity::Warning | Severity::Error => NotificationKind::Error,
+        };
+
+        self.push(kind, error.text().to_string(), Some(error));
+    }
+
+    pub(crate) fn dismiss(
+        &self,
+        id: u64,
+    ) {
+        self.queue
+            .write()
+            .notifications
+            .retain(|notification| notification.id != id);
+    }
+
+    fn push(
+        &self,
+        kind: NotificationKind,
+        message: String,
+        error: Option<ErrorPresentation>,
+    ) {
+        let mut queue = self.queue.write();
+        let id = queue.next_id;
+        queue.next_id += 1;
+        queue
+            .notifications
+            .push(Notification {
+                id,
+                kind,
+                message,
+                error,
+            });
+    }
+}
+
+#[allow(non_snake_case)]
+#[component(no_case_check)]
+pub(crate) fn NotificationHost(cx: Scope) -> Element {
+    // Setup hooks
+    let notifications = use_notifications(cx);
+    let queue = notifications.queue.read();
+
+    // NOTE: Older notifications wait until newer ones are dismissed
+    let skip = queue
+        .notifications
+        .len()
+        .saturating_sub(MAX_VISIBLE_NOTIFICATIONS);
+
+    render! {
+        div {
+            position: "fixed",
+            bottom: "1rem",
+            left: "50%",
+            transform: "translateX(-50%)",
+            display: "flex",
+            flex_direction: "column",
+            gap: "0.5rem",
+            z_index: "10",
+
+            for notification in queue.notifications.iter().skip(skip) {
+                NotificationItem {
+                    key: "{notification.id}",
+                    notification: notification.clone(),
+                }
+            }
+        }
+    }
+}
+
+#[allow(non_snake_case)]
+#[component(no_case_check)]
+fn NotificationItem(
+    cx: Scope,
+    notification: Notification,
+) -> Element {
+    // Setup hooks
+    let notifications = use_notifications(cx);
+
+    use_future(cx, (), {
+        let notifications = notifications.clone();
+        let id = notification.id;
+        let duration = notification.kind.duration();
+        move |_| async move {
+            async_std::task::sleep(duration).await;
+            notifications.dismiss(id);
+        }
+    });
+
+    render! {
+        div {
+            background_color: "var(--mdc-theme-surface, white)",
+            border_left: "4px solid {notification.kind.color()}",
+            box_shadow: "0 2px 6px rgba(0, 0, 0, 0.3)",
+            padding: "0.5rem 1rem",
+            min_width: "20rem",
+            display: "flex",
+            align_items: "center",
+            gap: "1rem",
+
+            match &notification.error {
+                | Some(error) => render! {
+                    ErrorView {
+                        error: error.clone(),
+                    }
+                },
+                | None => render! {
+                    label {
+                        notification.message.as_str(),
+                    }
+                },
+            }
+
+            span {
+                onclick: move |_| notifications.dismiss(notification.id),
+                MatButton {
+                    label: "Dismiss",
+                }
+            }
+        }
+    }
+}
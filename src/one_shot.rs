@@ -0,0 +1,42 @@
+use std::future::Future;
+
+use dioxus::prelude::{use_state, ScopeState, UseState};
+
+use crate::error_presentation::ErrorPresentation;
+
+/// The state of a side effect that runs once per visit of a route.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum OneShotStatus {
+    InProgress,
+    Succeeded,
+    Failed(ErrorPresentation),
+}
+
+/// Runs the task once when the component is mounted.
+///
+/// Re-renders do not start the task again, so single-use inputs such as
+/// an authorization code are consumed only once.
+pub(crate) fn use_one_shot<Task, F>(
+    cx: &ScopeState,
+    task: Task,
+) -> &UseState<OneShotStatus>
+where
+    Task: FnOnce() -> F,
+    F: Future<Output = Result<(), ErrorPresentation>> + 'static,
+{
+    // Setup hooks
+    let status = use_state(cx, || OneShotStatus::InProgress);
+
+    cx.use_hook(|| {
+        let status = status.clone();
+        let task = task();
+        cx.spawn(async move {
+            match task.await {
+                | Ok(()) => status.set(OneShotStatus::Succeeded),
+                | Err(error) => status.set(OneShotStatus::Failed(error)),
+            }
+        });
+    });
+
+    status
+}
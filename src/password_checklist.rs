@@ -0,0 +1,63 @@
+use dioxus::prelude::{
+    component, dioxus_elements, render, Element, GlobalAttributes,
+    IntoDynNode, Props, Scope,
+};
+use crate::credential::PasswordPolicy;
+
+#[allow(non_snake_case)]
+#[component(no_case_check)]
+pub(crate) fn PasswordChecklist(
+    cx: Scope,
+    password: String,
+    // NOTE: Not stripped by props, because None is passed explicitly
+    #[props(!optional)]
+    email: Option<String>,
+) -> Element {
+    let policy = PasswordPolicy::default();
+    let email = email.as_deref();
+    let strength = policy.strength(password, email);
+
+    if password.is_empty() {
+        return render! { div {} };
+    }
+
+    render! {
+        div {
+            padding: "0.5rem",
+
+            // NOTE: material-dioxus has no linear progress component
+            progress {
+                width: "100%",
+                max: 1,
+                value: "{strength.progress()}",
+            }
+
+            div {
+                "Strength: "
+                span { strength.label() }
+            }
+
+            for rule in policy.rules() {
+                if policy.is_satisfied(&rule, password, email) {
+                    render! {
+                        div {
+                            color: "green",
+                            label {
+                                "✓ {rule.description()}"
+                            }
+                        }
+                    }
+                } else {
+                    render! {
+                        div {
+                            color: "red",
+                            label {
+                                "✗ {rule.description()}"
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use dioxus::prelude::{
+    component, dioxus_elements, render, Element, GlobalAttributes, Scope,
+};
+
+use crate::generated::dotenv::{BUILD_CONFIG, PROFILE};
+
+const PRODUCTION_PROFILE: &str = "production";
+
+/// Whether the banner is shown, which is by default every profile other
+/// than production.
+fn is_shown() -> bool {
+    BUILD_CONFIG
+        .show_profile_banner
+        .unwrap_or(PROFILE != PRODUCTION_PROFILE)
+}
+
+/// A banner that tells the profile of a non-production build, so that
+/// staging is not confused with production.
+#[allow(non_snake_case)]
+#[component(no_case_check)]
+pub(crate) fn ProfileBanner(cx: Scope) -> Element {
+    if !is_shown() {
+        return None;
+    }
+
+    render! {
+        div {
+            width: "100%",
+            padding: "0.5rem",
+            text_align: "center",
+            color: "black",
+            background_color: "orange",
+            label {
+                "{PROFILE} build: not production"
+            }
+        }
+    }
+}
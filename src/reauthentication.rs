@@ -0,0 +1,829 @@
+use dioxus::prelude::{
+    component, dioxus_elements, fc_to_builder, render, to_owned, use_state,
+    Element, GlobalAttributes, IntoDynNode, Props, Scope, ScopeState,
+    UseState,
+};
+use dioxus_router::prelude::{use_navigator, Navigator};
+use fars::error::{CommonErrorCode, Error};
+use material_dioxus::{MatButton, MatTextField};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    async_action::{use_async_action, ActionButton, UseAsyncAction},
+    auth::{local_id_of, use_auth, UseAuth},
+    error_presentation::{
+        ErrorMessage, ErrorPresentation, ErrorView, Severity,
+    },
+    identity_toolkit::{
+        self,
+        change_email::send_verify_and_change_email,
+        change_password::change_password,
+        mfa::{
+            finalize_sign_in, sign_in_with_email_password, withdraw,
+            PasswordSignIn, PendingMfaSignIn,
+        },
+        TokenSession,
+    },
+    notification::{use_notifications, UseNotifications},
+    routings::{
+        oauth_google::revoke_stored_google_token,
+        route::Route,
+        sign_in_oauth::{authorize_with_google, OAuthIntent},
+    },
+    storage::{
+        self, StorageKind, PENDING_EMAIL_CHANGE_KEY, PENDING_OPERATION_KEY,
+    },
+};
+
+/// An account operation that Firebase rejects when the sign-in is
+/// not recent.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) enum SensitiveOperation {
+    ChangeEmail {
+        email: String,
+        // NOTE: To show the pending change only to the same account
+        #[serde(default)]
+        previous_email: Option<String>,
+    },
+    ChangePassword {
+        #[serde(skip)]
+        password: String,
+    },
+    RemoveSecondFactor {
+        mfa_enrollment_id: String,
+    },
+    DeleteAccount,
+}
+
+impl SensitiveOperation {
+    fn description(&self) -> &'static str {
+        match self {
+            | SensitiveOperation::ChangeEmail {
+                ..
+            } => "change your e-mail address",
+            | SensitiveOperation::ChangePassword {
+                ..
+            } => "change your password",
+            | SensitiveOperation::RemoveSecondFactor {
+                ..
+            } => "remove the second factor",
+            | SensitiveOperation::DeleteAccount => "delete your account",
+        }
+    }
+
+    // NOTE: Passwords must not be written into the storage
+    fn can_resume_after_redirect(&self) -> bool {
+        !matches!(
+            self,
+            SensitiveOperation::ChangePassword { .. }
+        )
+    }
+}
+
+/// An operation stored over the Google redirect with the account that
+/// confirmed it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct PendingOperation {
+    local_id: String,
+    operation: SensitiveOperation,
+}
+
+/// A new e-mail address waiting for the verification link to be opened.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct PendingEmailChange {
+    pub(crate) email: String,
+    pub(crate) previous_email: Option<String>,
+}
+
+/// The result of signing in again with the password.
+enum Reauthentication {
+    Completed,
+    /// The code of the authenticator app is asked in the dialog.
+    SecondFactorRequired,
+}
+
+pub(crate) fn requires_recent_login(error: &anyhow::Error) -> bool {
+    if let Some(error) = error.downcast_ref::<Error>() {
+        matches!(
+            error,
+            Error::ApiError {
+                error_code: CommonErrorCode::CredentialTooOldLoginAgain,
+                ..
+            }
+        )
+    } else if let Some(error) =
+        error.downcast_ref::<identity_toolkit::error::Error>()
+    {
+        matches!(
+            error,
+            identity_toolkit::error::Error::ApiError {
+                error_code,
+                ..
+            } if error_code == "CREDENTIAL_TOO_OLD_LOGIN_AGAIN"
+        )
+    } else {
+        false
+    }
+}
+
+/// A handle to run sensitive operations that asks for
+/// re-authentication and retries them when the sign-in is stale.
+#[derive(Clone)]
+pub(crate) struct UseSensitiveOperation {
+    auth: UseAuth,
+    notifications: UseNotifications,
+    navigator: Navigator,
+    pending: UseState<Option<SensitiveOperation>>,
+    second_factor: UseState<Option<PendingMfaSignIn>>,
+    // NOTE: Bumped to render again when the stored e-mail change is updated
+    email_change_revision: UseState<u64>,
+    // NOTE: Bumped to reload the second factors after one is removed
+    factors_revision: UseState<u64>,
+    action: UseAsyncAction,
+}
+
+pub(crate) fn use_sensitive_operation(
+    cx: &ScopeState
+) -> &UseSensitiveOperation {
+    let auth = use_auth(cx).clone();
+    let notifications = use_notifications(cx).clone();
+    let navigator = use_navigator(cx).clone();
+    let pending = use_state::<Option<SensitiveOperation>>(cx, || None).clone();
+    let second_factor =
+        use_state::<Option<PendingMfaSignIn>>(cx, || None).clone();
+    let email_change_revision = use_state(cx, || 0).clone();
+    let factors_revision = use_state(cx, || 0).clone();
+    let action = use_async_action(cx).clone();
+
+    cx.use_hook(|| UseSensitiveOperation {
+        auth,
+        notifications,
+        navigator,
+        pending,
+        second_factor,
+        email_change_revision,
+        factors_revision,
+        action,
+    })
+}
+
+impl UseSensitiveOperation {
+    pub(crate) fn pending(&self) -> Option<SensitiveOperation> {
+        self.pending
+            .current()
+            .as_ref()
+            .clone()
+    }
+
+    /// Asks for re-authentication before running the operation.
+    pub(crate) fn request_reauthentication(
+        &self,
+        operation: SensitiveOperation,
+    ) {
+        self.pending.set(Some(operation));
+    }
+
+    /// The password sign-in waiting for the second factor.
+    pub(crate) fn second_factor(&self) -> Option<PendingMfaSignIn> {
+        self.second_factor
+            .current()
+            .as_ref()
+            .clone()
+    }
+
+    pub(crate) fn cancel(&self) {
+        self.pending.set(None);
+        self.second_factor.set(None);
+    }
+
+    /// Changes when a second factor has been removed.
+    pub(crate) fn factors_revision(&self) -> u64 {
+        *self.factors_revision.current()
+    }
+
+    /// The in-flight state shared by the triggers of the operations.
+    pub(crate) fn action(&self) -> &UseAsyncAction {
+        &self.action
+    }
+
+    fn email_change_key(&self) -> Option<String> {
+        self.auth
+            .active_account_id()
+            .map(|local_id| {
+                storage::account_key(PENDING_EMAIL_CHANGE_KEY, &local_id)
+            })
+    }
+
+    fn stored_email_change(&self) -> Option<PendingEmailChange> {
+        storage::load::<PendingEmailChange>(
+            StorageKind::Local,
+            &self.email_change_key()?,
+        )
+    }
+
+    /// The pending e-mail change of the active account with the current
+    /// e-mail.
+    pub(crate) fn pending_email_change(
+        &self,
+        current_email: Option<&str>,
+    ) -> Option<PendingEmailChange> {
+        self.stored_email_change()
+            .filter(|pending| pending.previous_email.as_deref() == current_email)
+    }
+
+    /// Forgets the pending e-mail change if the current e-mail is the
+    /// new one, and returns whether it has been completed.
+    pub(crate) fn complete_email_change(
+        &self,
+        current_email: Option<&str>,
+    ) -> bool {
+        let is_completed = self
+            .stored_email_change()
+            .map(|pending| Some(pending.email.as_str()) == current_email)
+            .unwrap_or(false);
+
+        if is_completed {
+            self.clear_email_change();
+        }
+
+        is_completed
+    }
+
+    pub(crate) fn clear_email_change(&self) {
+        if let Some(key) = self.email_change_key() {
+            storage::remove(StorageKind::Local, &key);
+        }
+        self.email_change_revision
+            .modify(|revision| revision + 1);
+    }
+
+    pub(crate) fn spawn(
+        &self,
+        cx: &ScopeState,
+        operation: SensitiveOperation,
+    ) {
+        self.action
+            .run(cx, self.clone().run(operation));
+    }
+
+    /// Signs in again with the password and then runs the operation.
+    ///
+    /// The dialog asks for the second factor when it is enrolled.
+    pub(crate) fn spawn_with_password(
+        &self,
+        cx: &ScopeState,
+        email: String,
+        password: String,
+        operation: SensitiveOperation,
+    ) {
+        let this = self.clone();
+        self.action.run(cx, async move {
+            match this
+                .reauthenticate(email, password)
+                .await
+            {
+                | Ok(Reauthentication::Completed) => this.run(operation).await,
+                | Ok(Reauthentication::SecondFactorRequired) => {
+                    this.pending.set(Some(operation));
+                    Ok(())
+                },
+                | Err(error) => {
+                    this.notifications.error(error.clone());
+                    Err(error)
+                },
+            }
+        });
+    }
+
+    async fn reauthenticate(
+        &self,
+        email: String,
+        password: String,
+    ) -> Result<Reauthentication, ErrorPresentation> {
+        log::info!("Reauthenticate with password");
+        match sign_in_with_email_password(
+            &self.auth.config(),
+            email,
+            password,
+        )
+        .await
+        {
+            | Ok(PasswordSignIn::Completed(session)) => {
+                self.complete_reauthentication(session)?;
+                log::info!("Reauthenticate with password success");
+                Ok(Reauthentication::Completed)
+            },
+            | Ok(PasswordSignIn::MfaRequired(pending)) => {
+                log::info!("Reauthenticate with password requires MFA");
+                self.second_factor.set(Some(pending));
+                Ok(Reauthentication::SecondFactorRequired)
+            },
+            | Err(error) => {
+                log::error!("Reauthenticate with password failed: {:?}", error);
+                Err(ErrorPresentation::from(&error))
+            },
+        }
+    }
+
+    /// Verifies the code of the authenticator app for the password
+    /// sign-in.
+    async fn verify_second_factor(
+        &self,
+        mfa_enrollment_id: String,
+        verification_code: String,
+    ) -> Result<(), ErrorPresentation> {
+        let pending = match self.second_factor() {
+            | Some(pending) => pending,
+            | None => return Ok(()),
+        };
+
+        log::info!("Reauthenticate with second factor");
+        match finalize_sign_in(
+            &self.auth.config(),
+            &pending,
+            mfa_enrollment_id,
+            verification_code,
+        )
+        .await
+        {
+            | Ok(session) => {
+                self.second_factor.set(None);
+                self.complete_reauthentication(session)?;
+                log::info!("Reauthenticate with second factor success");
+                Ok(())
+            },
+            | Err(error) => {
+                log::error!(
+                    "Reauthenticate with second factor failed: {:?}",
+                    error
+                );
+                Err(ErrorPresentation::from(&error))
+            },
+        }
+    }
+
+    /// Replaces the session of the active account, refusing a session
+    /// of another account.
+    fn complete_reauthentication(
+        &self,
+        session: TokenSession,
+    ) -> Result<(), ErrorPresentation> {
+        if local_id_of(&session.session) != self.auth.active_account_id() {
+            log::error!("Reauthenticated with another account");
+            self.second_factor.set(None);
+            return Err(ErrorPresentation::new(
+                ErrorMessage::AccountMismatch,
+                Severity::Error,
+                "Reauthenticated account differs from the active one"
+                    .to_string(),
+            ));
+        }
+
+        self.auth
+            .complete_sign_in(session.session, Some(session.refresh_token));
+        Ok(())
+    }
+
+    /// Resumes an operation that was interrupted by the Google
+    /// re-authentication redirect.
+    ///
+    /// The operation is dropped unless the active account is the one
+    /// that confirmed it.
+    pub(crate) fn resume_after_redirect(
+        &self,
+        cx: &ScopeState,
+    ) {
+        let pending = match storage::take::<PendingOperation>(
+            StorageKind::Session,
+            PENDING_OPERATION_KEY,
+        ) {
+            | Some(pending) => pending,
+            | None => return,
+        };
+
+        if self.auth.active_account_id() != Some(pending.local_id.clone()) {
+            log::error!(
+                "Drop sensitive operation of another account: {:?}",
+                pending.operation
+            );
+            self.notifications
+                .error(ErrorPresentation::new(
+                    ErrorMessage::AccountMismatch,
+                    Severity::Error,
+                    "Active account differs from the confirmed one"
+                        .to_string(),
+                ));
+            return;
+        }
+
+        log::info!(
+            "Resume sensitive operation: {:?}",
+            pending.operation
+        );
+        self.spawn(cx, pending.operation);
+    }
+
+    async fn run(
+        self,
+        operation: SensitiveOperation,
+    ) -> Result<(), ErrorPresentation> {
+        let snapshot = match self.auth.snapshot() {
+            | Some(snapshot) => snapshot,
+            | None => return Ok(()),
+        };
+
+        log::info!("Run sensitive operation: {:?}", operation);
+        let session = snapshot.session.clone();
+        let local_id = local_id_of(&session).unwrap_or_default();
+        let result: anyhow::Result<Option<TokenSession>> = match operation
+            .clone()
+        {
+            // NOTE: The address is changed when the new one is verified
+            | SensitiveOperation::ChangeEmail {
+                email,
+                ..
+            } => send_verify_and_change_email(session.id_token.clone(), email)
+                .await
+                .map(|_| None)
+                .map_err(anyhow::Error::from),
+            // NOTE: The issued token replaces the revoked persisted one
+            | SensitiveOperation::ChangePassword {
+                password,
+            } => change_password(
+                &self.auth.config(),
+                session.id_token.clone(),
+                password,
+            )
+            .await
+            .map(Some),
+            // NOTE: Withdrawing revokes the previous tokens as well
+            | SensitiveOperation::RemoveSecondFactor {
+                mfa_enrollment_id,
+            } => withdraw(
+                &self.auth.config(),
+                session.id_token.clone(),
+                mfa_enrollment_id,
+            )
+            .await
+            .map(Some),
+            | SensitiveOperation::DeleteAccount => session
+                .delete_account()
+                .await
+                .map(|_| None)
+                .map_err(anyhow::Error::from),
+        };
+
+        match result {
+            | Ok(new_session) => {
+                log::info!("Sensitive operation success");
+                if let Some(new_session) = new_session {
+                    self.auth
+                        .update_token_session(&snapshot, new_session);
+                }
+
+                match operation {
+                    | SensitiveOperation::ChangeEmail {
+                        email,
+                        previous_email,
+                    } => {
+                        let pending = PendingEmailChange {
+                            email,
+                            previous_email,
+                        };
+                        storage::save(
+                            StorageKind::Local,
+                            &storage::account_key(
+                                PENDING_EMAIL_CHANGE_KEY,
+                                &local_id,
+                            ),
+                            &pending,
+                        );
+                        self.email_change_revision
+                            .modify(|revision| revision + 1);
+                        self.notifications.success(
+                            "Verification e-mail has been sent to the new address.",
+                        );
+                    },
+                    | SensitiveOperation::ChangePassword {
+                        ..
+                    } => {
+                        self.notifications
+                            .success("Password has been changed.");
+                    },
+                    | SensitiveOperation::RemoveSecondFactor {
+                        ..
+                    } => {
+                        self.factors_revision
+                            .modify(|revision| revision + 1);
+                        self.notifications
+                            .success("Second factor has been removed.");
+                    },
+                    | SensitiveOperation::DeleteAccount => {
+                        revoke_stored_google_token().await;
+                        storage::clear_account_data(&local_id);
+                        self.auth.sign_out_account(&local_id);
+                        self.navigator
+                            .push(Route::Home {});
+                        self.notifications
+                            .success("Account has been deleted.");
+                    },
+                }
+                Ok(())
+            },
+            | Err(error) if requires_recent_login(&error) => {
+                log::info!("Sensitive operation requires recent login");
+                self.pending.set(Some(operation));
+                Ok(())
+            },
+            | Err(error) => {
+                log::error!("Sensitive operation failed: {:?}", error);
+                let error = ErrorPresentation::from(&error);
+                self.notifications.error(error.clone());
+                Err(error)
+            },
+        }
+    }
+}
+
+#[allow(non_snake_case)]
+#[component(no_case_check)]
+pub(crate) fn ReauthenticationDialog<'a>(
+    cx: Scope<'a>,
+    operation: &'a UseSensitiveOperation,
+    #[props(!optional)]
+    email: Option<String>,
+    // NOTE: Provider IDs of the current user such as "password"
+    providers: Vec<String>,
+) -> Element<'a> {
+    // Setup hooks
+    let password = use_state(cx, String::new);
+    let code = use_state(cx, String::new);
+    let selected = use_state(cx, || 0_usize);
+    let confirm_action = use_async_action(cx);
+
+    let pending = match operation.pending() {
+        | Some(pending) => pending,
+        | None => return render! { div {} },
+    };
+    let second_factor = operation.second_factor();
+    let has_password = providers
+        .iter()
+        .any(|provider| provider == "password");
+    let has_google = providers
+        .iter()
+        .any(|provider| provider == "google.com");
+
+    render! {
+        div {
+            position: "fixed",
+            top: "0",
+            left: "0",
+            width: "100%",
+            height: "100%",
+            background_color: "rgba(0, 0, 0, 0.5)",
+            display: "flex",
+            align_items: "center",
+            justify_content: "center",
+            z_index: "5",
+
+            div {
+                background_color: "var(--mdc-theme-surface, white)",
+                padding: "1.5rem",
+                max_width: "28rem",
+
+                h2 { "Confirm it's you" }
+
+                div {
+                    label {
+                        "Please sign in again to {pending.description()}."
+                    }
+                }
+
+                br {}
+
+                if let Some(second_factor) = second_factor {
+                    render! {
+                        div {
+                            label {
+                                "Enter the code from your authenticator app."
+                            }
+                        }
+
+                        // NOTE: Let the user choose when several apps are enrolled
+                        if second_factor.factors.len() > 1 {
+                            render! {
+                                div {
+                                    for (index, factor) in second_factor.factors.iter().enumerate() {
+                                        render! {
+                                            span {
+                                                onclick: move |_| selected.set(index),
+                                                MatButton {
+                                                    label: factor.display_name.clone().unwrap_or("Authenticator app".to_string()),
+                                                    outlined: true,
+                                                    disabled: *selected.get() == index,
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+
+                        div {
+                            MatTextField {
+                                label: "Verification code",
+                                value: code.get(),
+                                _oninput: {
+                                    to_owned![code];
+                                    move |event: String| {
+                                        code.set(event)
+                                    }
+                                }
+                            }
+                        }
+
+                        div {
+                            ActionButton {
+                                label: "Verify",
+                                action: confirm_action,
+                                disabled: !is_verification_code(code.get()),
+                                onclick: move |_| {
+                                    if let Some(factor) = second_factor.factors.get(*selected.get()) {
+                                        reauthenticate_with_second_factor(
+                                            cx,
+                                            operation,
+                                            confirm_action,
+                                            factor.mfa_enrollment_id.clone(),
+                                            code.get().trim().to_string(),
+                                        )
+                                    }
+                                },
+                            }
+                        }
+                    }
+                } else if has_password {
+                    render! {
+                        div {
+                            MatTextField {
+                                label: "Password",
+                                value: password.get().clone().replace(|_| true, "*"),
+                                _oninput: {
+                                    to_owned![password];
+                                    move |event: String| {
+                                        password.set(event)
+                                    }
+                                }
+                            }
+                        }
+
+                        div {
+                            ActionButton {
+                                label: "Confirm",
+                                action: confirm_action,
+                                disabled: password.get().is_empty() || email.is_none(),
+                                onclick: move |_| {
+                                    if let Some(email) = email {
+                                        reauthenticate_with_password(
+                                            cx,
+                                            operation,
+                                            confirm_action,
+                                            email.clone(),
+                                            password.get().clone(),
+                                        )
+                                    }
+                                },
+                            }
+                        }
+                    }
+                }
+
+                if has_google {
+                    render! {
+                        div {
+                            span {
+                                onclick: move |_| reauthenticate_with_google(operation, email.clone()),
+                                MatButton {
+                                    label: "Continue with Google",
+                                    outlined: true,
+                                }
+                            }
+                        }
+                    }
+                }
+
+                if let Some(error) = confirm_action.error() {
+                    render! {
+                        ErrorView {
+                            error: error.clone(),
+                        }
+                    }
+                }
+
+                br {}
+
+                div {
+                    span {
+                        onclick: move |_| {
+                            // NOTE: The dialog stays mounted for the next operation
+                            confirm_action.reset();
+                            password.set(String::new());
+                            code.set(String::new());
+                            operation.cancel();
+                        },
+                        MatButton {
+                            label: "Cancel",
+                            outlined: true,
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn reauthenticate_with_password(
+    cx: &ScopeState,
+    operation: &UseSensitiveOperation,
+    action: &UseAsyncAction,
+    email: String,
+    password: String,
+) {
+    // Setup hooks
+    let operation = operation.clone();
+
+    action.run(cx, async move {
+        match operation
+            .reauthenticate(email, password)
+            .await?
+        {
+            | Reauthentication::Completed => {
+                resume_pending(operation).await;
+                Ok(())
+            },
+            // NOTE: The dialog shows the code input next
+            | Reauthentication::SecondFactorRequired => Ok(()),
+        }
+    });
+}
+
+fn reauthenticate_with_second_factor(
+    cx: &ScopeState,
+    operation: &UseSensitiveOperation,
+    action: &UseAsyncAction,
+    mfa_enrollment_id: String,
+    verification_code: String,
+) {
+    // Setup hooks
+    let operation = operation.clone();
+
+    action.run(cx, async move {
+        operation
+            .verify_second_factor(mfa_enrollment_id, verification_code)
+            .await?;
+        resume_pending(operation).await;
+        Ok(())
+    });
+}
+
+async fn resume_pending(operation: UseSensitiveOperation) {
+    if let Some(pending) = operation.pending() {
+        operation.cancel();
+        // NOTE: Failures are notified by the operation itself
+        let _ = operation.run(pending).await;
+    }
+}
+
+fn is_verification_code(code: &str) -> bool {
+    let code = code.trim();
+
+    !code.is_empty()
+        && code
+            .chars()
+            .all(|character| character.is_ascii_digit())
+}
+
+fn reauthenticate_with_google(
+    operation: &UseSensitiveOperation,
+    email: Option<String>,
+) {
+    log::info!("Reauthenticate with Google");
+    let local_id = operation.auth.active_account_id();
+    if let (Some(pending), Some(local_id)) = (operation.pending(), local_id) {
+        if pending.can_resume_after_redirect() {
+            storage::save(
+                StorageKind::Session,
+                PENDING_OPERATION_KEY,
+                &PendingOperation {
+                    local_id,
+                    operation: pending,
+                },
+            );
+        }
+    }
+
+    if let Err(error) =
+        authorize_with_google(OAuthIntent::Reauthenticate, email)
+    {
+        log::error!("Failed to authorize with Google: {:?}", error);
+    }
+}
@@ -1,3 +1,4 @@
+pub(crate) mod auth_action;
 pub(crate) mod dashboard;
 pub(crate) mod home;
 pub(crate) mod not_found;
@@ -6,5 +7,8 @@ pub(crate) mod reset_password;
 pub(crate) mod route;
 pub(crate) mod sign_in;
 pub(crate) mod sign_in_anonymously;
+pub(crate) mod sign_in_email_link;
+pub(crate) mod sign_in_mfa;
 pub(crate) mod sign_in_oauth;
 pub(crate) mod sign_up;
+pub(crate) mod verify_email;
@@ -0,0 +1,491 @@
+use std::fmt::Display;
+
+use dioxus::prelude::{
+    component, dioxus_elements, fc_to_builder, render, to_owned, use_future,
+    use_state, Element, IntoDynNode, Props, Scope, ScopeState, UseState,
+};
+use dioxus_router::{hooks::use_navigator, prelude::FromQuery};
+use material_dioxus::{button::MatButton, text_inputs::MatTextField};
+
+use crate::{
+    async_action::{use_async_action, ActionButton, UseAsyncAction},
+    auth::{use_auth, UseAuth},
+    credential::PasswordPolicy,
+    error_presentation::{
+        ErrorMessage, ErrorPresentation, ErrorView, Severity,
+    },
+    identity_toolkit::action_code::{
+        apply_action_code, check_action_code, confirm_password_reset,
+        AppliedActionCode,
+    },
+    notification::{use_notifications, UseNotifications},
+    password_checklist::PasswordChecklist,
+    routings::{
+        oauth_google::parse_query_str,
+        route::Route,
+        sign_in_email_link::{
+            EmailLinkQuery, FinishEmailLinkSignIn, SIGN_IN_MODE,
+        },
+    },
+    storage::{self, StorageKind, PENDING_EMAIL_CHANGE_KEY},
+};
+
+/// Handles the links in e-mails sent by Firebase Auth.
+#[allow(non_snake_case)]
+#[component(no_case_check)]
+pub(crate) fn AuthAction(
+    cx: Scope,
+    query: AuthActionQuery,
+) -> Element {
+    log::info!("Auth action: {:?}", query.mode);
+
+    if query.oob_code.is_empty() {
+        return render! {
+            h1 { "Invalid link" }
+
+            ErrorView {
+                error: ErrorPresentation::new(
+                    ErrorMessage::InvalidActionCode,
+                    Severity::Warning,
+                    query.to_string(),
+                ),
+            }
+        };
+    }
+
+    match query.mode {
+        | AuthActionMode::ResetPassword => render! {
+            ResetPasswordAction {
+                oob_code: query.oob_code.clone(),
+            }
+        },
+        // NOTE: Sign-in links share the action URL with other templates
+        | AuthActionMode::SignIn => render! {
+            FinishEmailLinkSignIn {
+                query: EmailLinkQuery {
+                    oob_code: query.oob_code.clone(),
+                    mode: SIGN_IN_MODE.to_string(),
+                },
+            }
+        },
+        | AuthActionMode::VerifyEmail
+        | AuthActionMode::VerifyAndChangeEmail
+        | AuthActionMode::RecoverEmail => {
+            render! {
+                ApplyAction {
+                    mode: query.mode.clone(),
+                    oob_code: query.oob_code.clone(),
+                }
+            }
+        },
+        | AuthActionMode::Unknown(_) => render! {
+            h1 { "Invalid link" }
+
+            ErrorView {
+                error: ErrorPresentation::new(
+                    ErrorMessage::InvalidActionCode,
+                    Severity::Warning,
+                    query.to_string(),
+                ),
+            }
+        },
+    }
+}
+
+#[allow(non_snake_case)]
+#[component(no_case_check)]
+fn ResetPasswordAction(
+    cx: Scope,
+    oob_code: String,
+) -> Element {
+    // Setup hooks
+    let notifications = use_notifications(cx);
+    let password = use_state(cx, String::new);
+    let confirm_password = use_state(cx, String::new);
+    let reset_action = use_async_action(cx);
+    let navigator = use_navigator(cx);
+
+    // NOTE: Check the code before asking for a new password
+    let check = use_future(cx, (), |_| {
+        let oob_code = oob_code.clone();
+        async move {
+            log::info!("Check password reset code");
+            match check_action_code(oob_code).await {
+                | Ok(info) if info.request_type == "PASSWORD_RESET" => {
+                    log::info!("Check password reset code success");
+                    Ok(info.email)
+                },
+                | Ok(info) => {
+                    log::error!(
+                        "Unexpected request type: {}",
+                        info.request_type
+                    );
+                    Err(ErrorPresentation::new(
+                        ErrorMessage::InvalidActionCode,
+                        Severity::Warning,
+                        info.request_type,
+                    ))
+                },
+                | Err(error) => {
+                    log::error!("Check password reset code failed: {:?}", error);
+                    Err(ErrorPresentation::from(&error))
+                },
+            }
+        }
+    });
+
+    render! {
+        h1 { "Reset password" }
+
+        match check.value() {
+            | None => render! {
+                div {
+                    "Checking the link..."
+                }
+            },
+            | Some(Err(error)) => render! {
+                ErrorView {
+                    error: error.clone(),
+                }
+
+                br {}
+
+                div {
+                    span {
+                        onclick: move |_| {
+                            navigator.push(Route::ResetPassword { });
+                        },
+                        MatButton {
+                            label: "Request a new link",
+                            outlined: true,
+                        }
+                    }
+                }
+            },
+            | Some(Ok(email)) => render! {
+                div {
+                    label {
+                        "Set a new password for {email}."
+                    }
+                }
+
+                div {
+                    MatTextField {
+                        label: "New password",
+                        value: password.get().clone().replace(|_| true, "*"),
+                        _oninput: {
+                            to_owned![password];
+                            move |event: String| {
+                                password.set(event)
+                            }
+                        }
+                    }
+
+                    PasswordChecklist {
+                        password: password.get().clone(),
+                        email: Some(email.clone()),
+                    }
+                }
+
+                div {
+                    MatTextField {
+                        label: "Confirm password",
+                        value: confirm_password.get().clone().replace(|_| true, "*"),
+                        _oninput: {
+                            to_owned![confirm_password];
+                            move |event: String| {
+                                confirm_password.set(event)
+                            }
+                        }
+                    }
+                }
+
+                div {
+                    ActionButton {
+                        label: "Reset password",
+                        action: reset_action,
+                        disabled: !can_reset(email, password, confirm_password),
+                        onclick: move |_| {
+                            reset_password(cx, notifications, reset_action, oob_code.clone(), password.get().clone())
+                        },
+                    }
+                }
+
+                br {}
+
+                div {
+                    if let Some(error_message) = reset_action.error() {
+                        render! {
+                            ErrorView {
+                                error: error_message.clone(),
+                            }
+                        }
+                    }
+                }
+            },
+        }
+    }
+}
+
+#[allow(non_snake_case)]
+#[component(no_case_check)]
+fn ApplyAction(
+    cx: Scope,
+    mode: AuthActionMode,
+    oob_code: String,
+) -> Element {
+    // Setup hooks
+    let auth = use_auth(cx);
+    let navigator = use_navigator(cx);
+
+    let apply = use_future(cx, (), |_| {
+        let auth = auth.clone();
+        let mode = mode.clone();
+        let oob_code = oob_code.clone();
+        async move {
+            log::info!("Apply action code");
+            match apply_action_code(oob_code).await {
+                | Ok(applied) => {
+                    log::info!("Apply action code success");
+                    // NOTE: Changing or reverting the e-mail revokes the
+                    // sessions of the account the link belongs to
+                    if mode != AuthActionMode::VerifyEmail {
+                        sign_out_applied_account(&auth, &applied);
+                    }
+                    Ok(applied.email.unwrap_or_default())
+                },
+                | Err(error) => {
+                    log::error!("Apply action code failed: {:?}", error);
+                    Err(ErrorPresentation::from(&error))
+                },
+            }
+        }
+    });
+
+    let title = match mode {
+        | AuthActionMode::RecoverEmail => "Recover e-mail address",
+        | AuthActionMode::VerifyAndChangeEmail => "Change e-mail address",
+        | _ => "Verify e-mail address",
+    };
+    let next_route = match auth.is_signed_in() {
+        | true => Route::Dashboard {},
+        | false => Route::SignIn {},
+    };
+
+    render! {
+        h1 { title }
+
+        match apply.value() {
+            | None => render! {
+                div {
+                    "Applying the link..."
+                }
+            },
+            | Some(Err(error)) => render! {
+                ErrorView {
+                    error: error.clone(),
+                }
+            },
+            | Some(Ok(email)) if *mode == AuthActionMode::RecoverEmail => render! {
+                div {
+                    label {
+                        "Your e-mail address has been restored to {email}."
+                    }
+                }
+
+                div {
+                    label {
+                        "If you did not request the change, please reset your password."
+                    }
+                }
+
+                br {}
+
+                div {
+                    span {
+                        onclick: move |_| {
+                            navigator.push(Route::ResetPassword { });
+                        },
+                        MatButton {
+                            label: "Reset password",
+                            outlined: true,
+                        }
+                    }
+                }
+            },
+            | Some(Ok(email)) if *mode == AuthActionMode::VerifyAndChangeEmail => render! {
+                div {
+                    label {
+                        "Your e-mail address has been changed to {email}. Please sign in again."
+                    }
+                }
+            },
+            | Some(Ok(email)) => render! {
+                div {
+                    label {
+                        "Your e-mail address {email} has been verified."
+                    }
+                }
+            },
+        }
+
+        br {}
+
+        div {
+            span {
+                onclick: move |_| {
+                    navigator.push(next_route.clone());
+                },
+                MatButton {
+                    label: "Continue",
+                    outlined: true,
+                }
+            }
+        }
+    }
+}
+
+fn sign_out_applied_account(
+    auth: &UseAuth,
+    applied: &AppliedActionCode,
+) {
+    // NOTE: The e-mail is the new or restored one, which may differ from
+    // the name of the account
+    let local_id = applied
+        .local_id
+        .clone()
+        .or_else(|| {
+            auth.accounts()
+                .into_iter()
+                .find(|account| Some(&account.name) == applied.email.as_ref())
+                .map(|account| account.local_id)
+        });
+
+    match local_id {
+        | Some(local_id) => {
+            storage::remove(
+                StorageKind::Local,
+                &storage::account_key(PENDING_EMAIL_CHANGE_KEY, &local_id),
+            );
+            auth.sign_out_account(&local_id);
+        },
+        | None => log::info!("Account of the link is not signed in"),
+    }
+}
+
+fn can_reset(
+    email: &str,
+    password: &UseState<String>,
+    confirm_password: &UseState<String>,
+) -> bool {
+    let password = password.get();
+    let confirm_password = confirm_password.get();
+
+    PasswordPolicy::default().is_valid(password, Some(email))
+        && password == confirm_password
+}
+
+fn reset_password(
+    cx: &ScopeState,
+    notifications: &UseNotifications,
+    action: &UseAsyncAction,
+    oob_code: String,
+    password: String,
+) {
+    // Setup hooks
+    let notifications = notifications.clone();
+    let navigation = use_navigator(cx).clone();
+
+    action.run(cx, {
+        async move {
+            log::info!("Confirm password reset");
+            match confirm_password_reset(oob_code, password).await {
+                | Ok(_) => {
+                    log::info!("Confirm password reset success");
+                    notifications.success("Password has been reset.");
+                    navigation.push(Route::SignIn {});
+                    Ok(())
+                },
+                | Err(error) => {
+                    log::error!("Confirm password reset failed: {:?}", error);
+                    Err(ErrorPresentation::from(&error))
+                },
+            }
+        }
+    })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum AuthActionMode {
+    ResetPassword,
+    VerifyEmail,
+    VerifyAndChangeEmail,
+    RecoverEmail,
+    SignIn,
+    Unknown(String),
+}
+
+impl AuthActionMode {
+    fn as_str(&self) -> &str {
+        match self {
+            | AuthActionMode::ResetPassword => "resetPassword",
+            | AuthActionMode::VerifyEmail => "verifyEmail",
+            | AuthActionMode::VerifyAndChangeEmail => "verifyAndChangeEmail",
+            | AuthActionMode::RecoverEmail => "recoverEmail",
+            | AuthActionMode::SignIn => SIGN_IN_MODE,
+            | AuthActionMode::Unknown(mode) => mode,
+        }
+    }
+}
+
+impl From<&str> for AuthActionMode {
+    fn from(mode: &str) -> Self {
+        match mode {
+            | "resetPassword" => AuthActionMode::ResetPassword,
+            | "verifyEmail" => AuthActionMode::VerifyEmail,
+            | "verifyAndChangeEmail" => AuthActionMode::VerifyAndChangeEmail,
+            | "recoverEmail" => AuthActionMode::RecoverEmail,
+            | SIGN_IN_MODE => AuthActionMode::SignIn,
+            | _ => AuthActionMode::Unknown(mode.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct AuthActionQuery {
+    pub mode: AuthActionMode,
+    pub oob_code: String,
+}
+
+impl Display for AuthActionQuery {
+    fn fmt(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        let mut query = String::new();
+
+        query.push_str("mode=");
+        query.push_str(self.mode.as_str());
+
+        query.push_str("&oobCode=");
+        query.push_str(&self.oob_code);
+
+        write!(f, "{}", query)
+    }
+}
+
+impl FromQuery for AuthActionQuery {
+    fn from_query(query: &str) -> Self {
+        let params = parse_query_str(query);
+        AuthActionQuery {
+            mode: params
+                .get("mode")
+                .map(|mode| AuthActionMode::from(mode.as_str()))
+                .unwrap_or(AuthActionMode::Unknown(String::new())),
+            oob_code: params
+                .get("oobCode")
+                .cloned()
+                .unwrap_or_default(),
+        }
+    }
+}
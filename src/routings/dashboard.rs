@@ -1,8 +1,7 @@
-use async_std::sync::Mutex;
 use dioxus::prelude::{
     component, dioxus_elements, fc_to_builder, render, to_owned, use_future,
-    use_shared_state, use_state, Element, GlobalAttributes, IntoDynNode, Scope,
-    Scoped, UseFuture, UseSharedState, UseState,
+    use_state, Element, GlobalAttributes, IntoDynNode, Scope, Scoped,
+    UseFuture, UseState,
 };
 use dioxus_router::hooks::use_navigator;
 use fars::{
@@ -10,14 +9,34 @@ use fars::{
     Session,
 };
 use material_dioxus::{button::MatButton, text_inputs::MatTextField};
-use std::sync::Arc;
 
-use crate::application_context::ApplicationContext;
-use crate::routings::route::Route;
+use crate::app_config::{self, Provider};
+use crate::application_context::AuthStatus;
+use crate::async_action::{use_async_action, ActionButton, UseAsyncAction};
+use crate::auth::{
+    is_anonymous, requires_email_verification, use_auth, UseAuth,
+};
+use crate::credential::{is_valid_email, PasswordPolicy};
+use crate::email_hint::EmailHint;
+use crate::error_presentation::ErrorPresentation;
+use crate::mfa_settings::MfaSettings;
+use crate::notification::{use_notifications, UseNotifications};
+use crate::password_checklist::PasswordChecklist;
+use crate::reauthentication::{
+    use_sensitive_operation, ReauthenticationDialog, SensitiveOperation,
+    UseSensitiveOperation,
+};
+use crate::routings::{
+    route::Route,
+    sign_in_oauth::{authorize_with_google, OAuthIntent},
+};
+
+const RECENT_LOGIN_MILLISECONDS: f64 = 5.0 * 60.0 * 1000.0;
 
 enum TabState {
     Profile,
     Credentials,
+    Security,
     DeleteAccount,
 }
 
@@ -25,44 +44,128 @@ enum TabState {
 #[component(no_case_check)]
 pub(crate) fn Dashboard(cx: Scope) -> Element {
     // Setup hooks
-    let context =
-        use_shared_state::<Arc<Mutex<ApplicationContext>>>(cx).unwrap();
+    let auth = use_auth(cx);
     let display_name = use_state(cx, String::new);
     let photo_url = use_state(cx, String::new);
     let email = use_state(cx, String::new);
+    let current_password = use_state(cx, String::new);
     let password = use_state(cx, String::new);
     let confirm_password = use_state(cx, String::new);
     let link_email = use_state(cx, String::new);
     let link_password = use_state(cx, String::new);
     let link_confirm_password = use_state(cx, String::new);
-
-    let fetch_user_data = use_future(cx, (), move |_| {
-        let context = context.clone();
+    let delete_confirmation = use_state(cx, String::new);
+    let upgrade_email = use_state(cx, String::new);
+    let upgrade_password = use_state(cx, String::new);
+    let upgrade_confirm_password = use_state(cx, String::new);
+    let confirm_sign_out = use_state(cx, || false);
+    let profile_action = use_async_action(cx);
+    let provider_action = use_async_action(cx);
+    let notifications = use_notifications(cx);
+    let navigator = use_navigator(cx);
+    let sensitive_operation = use_sensitive_operation(cx);
+
+    // NOTE: Resume an operation interrupted by Google re-authentication
+    cx.use_hook(|| sensitive_operation.resume_after_redirect(cx));
+
+    // NOTE: Fetch again when another account is selected
+    let active_account_id = auth.active_account_id();
+    let fetch_user_data = use_future(cx, (&active_account_id,), move |_| {
+        let auth = auth.clone();
+        let notifications = notifications.clone();
+        let navigator = navigator.clone();
+        let sensitive_operation = sensitive_operation.clone();
         async move {
-            let context = context.clone();
-            let context = context.read();
-            let mut context = context.lock().await;
-            let session: Option<Session> = context.auth_session.clone();
-            match fetch_user_data_helper(session).await {
-                | Some((new_session, user_data)) => {
-                    context.auth_session = Some(new_session);
-                    Some(user_data)
+            match auth.begin_refresh() {
+                | Some(snapshot) => {
+                    match fetch_user_data_helper(snapshot.session.clone())
+                        .await
+                    {
+                        | Some((new_session, user_data)) => {
+                            auth.update_session(&snapshot, new_session);
+                            // NOTE: The new e-mail address has been verified
+                            if sensitive_operation
+                                .complete_email_change(user_data.email.as_deref())
+                            {
+                                notifications.success(
+                                    "E-mail address has been changed.",
+                                );
+                            }
+                            // NOTE: Block unverified password accounts
+                            if requires_email_verification(&user_data) {
+                                log::info!("Redirect to verify email");
+                                navigator.push(Route::VerifyEmail {});
+                            }
+                            Some(user_data)
+                        },
+                        | None => {
+                            auth.end_refresh(&snapshot);
+                            None
+                        },
+                    }
+                },
+                | None => {
+                    log::error!("Auth context is not available");
+                    None
                 },
-                | None => None,
             }
         }
     });
 
     let tab_state = use_state(cx, || TabState::Profile);
-
-    redirect_to_home_if_not_logged_in(cx, context);
+    let user_data = fetch_user_data
+        .value()
+        .and_then(|user_data| user_data.as_ref());
+    let user_email = user_data.and_then(|user_data| user_data.email.clone());
+    let user_providers: Vec<String> = user_data
+        .and_then(|user_data| user_data.provider_user_info.as_ref())
+        .map(|providers| {
+            providers
+                .iter()
+                .map(|provider| provider.provider_id.clone())
+                .collect()
+        })
+        .unwrap_or_default();
+    let has_password_provider = user_providers
+        .iter()
+        .any(|provider| provider == "password");
+    let is_anonymous = user_data
+        .map(is_anonymous)
+        .unwrap_or(false);
+
+    redirect_to_home_if_not_logged_in(cx, auth);
 
     render! {
         h1 { "Dashboard" }
 
+        render_account_switcher(cx, auth)
+
+        if auth.status() == AuthStatus::Restoring {
+            render! {
+                div {
+                    "Restoring accounts..."
+                }
+            }
+        }
+
+        if auth.status() == AuthStatus::Refreshing {
+            render! {
+                div {
+                    "Refreshing session..."
+                }
+            }
+        }
+
         div {
             span {
-                onclick: |_| sign_out(cx),
+                onclick: move |_| {
+                    // NOTE: Anonymous accounts cannot be signed in again
+                    if is_anonymous {
+                        confirm_sign_out.set(true);
+                    } else {
+                        sign_out(cx, auth, notifications);
+                    }
+                },
                 MatButton {
                     label: "Sign out",
                     outlined: true,
@@ -70,8 +173,49 @@ pub(crate) fn Dashboard(cx: Scope) -> Element {
             }
         }
 
+        if *confirm_sign_out.get() {
+            render! {
+                div {
+                    outline: "1px solid red",
+                    padding: "10px",
+
+                    div {
+                        color: "red",
+                        label {
+                            "You are signed in as a guest. Signing out will permanently lose this account and its data."
+                        }
+                    }
+
+                    div {
+                        span {
+                            onclick: |_| {
+                                confirm_sign_out.set(false);
+                                sign_out(cx, auth, notifications);
+                            },
+                            MatButton {
+                                label: "Sign out anyway",
+                                outlined: true,
+                            }
+                        }
+
+                        span {
+                            onclick: |_| confirm_sign_out.set(false),
+                            MatButton {
+                                label: "Cancel",
+                                outlined: true,
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
         br {}
 
+        if is_anonymous {
+            render_upgrade_account_panel(cx, auth, notifications, provider_action, upgrade_email, upgrade_password, upgrade_confirm_password)
+        }
+
         div {
             span {
                 onclick: |_| tab_state.set(TabState::Profile),
@@ -91,6 +235,15 @@ pub(crate) fn Dashboard(cx: Scope) -> Element {
                 }
             }
 
+            span {
+                onclick: |_| tab_state.set(TabState::Security),
+                MatButton {
+                    label: "Security",
+                    outlined: true,
+                    disabled: matches!(tab_state.get(), TabState::Security),
+                }
+            }
+
             span {
                 onclick: |_| tab_state.set(TabState::DeleteAccount),
                 MatButton {
@@ -103,22 +256,185 @@ pub(crate) fn Dashboard(cx: Scope) -> Element {
 
         br {}
 
+        ReauthenticationDialog {
+            operation: sensitive_operation,
+            email: user_email.clone(),
+            providers: user_providers,
+        }
+
         match tab_state.get() {
             | TabState::Profile => {
-                render_profile_tab(cx, display_name, photo_url, fetch_user_data)
+                render_profile_tab(cx, auth, notifications, profile_action, display_name, photo_url, fetch_user_data)
             },
             | TabState::Credentials => {
-                render_credentials_tab(cx, email, password, confirm_password, link_email, link_password, link_confirm_password)
+                render_credentials_tab(cx, auth, notifications, provider_action, sensitive_operation, user_email, has_password_provider, email, current_password, password, confirm_password, link_email, link_password, link_confirm_password)
+            },
+            | TabState::Security => render! {
+                MfaSettings {
+                    operation: sensitive_operation,
+                    email: user_email.clone(),
+                }
             },
             | TabState::DeleteAccount => {
-                render_delete_account_tab(cx)
+                render_delete_account_tab(cx, sensitive_operation, user_data, delete_confirmation)
             },
         }
     }
 }
 
+fn render_account_switcher<'a>(
+    cx: Scope<'a>,
+    auth: &'a UseAuth,
+) -> Element<'a> {
+    // Setup hooks
+    let navigator = use_navigator(cx);
+
+    render! {
+        div {
+            for account in auth.accounts() {
+                render! {
+                    span {
+                        onclick: {
+                            let local_id = account.local_id.clone();
+                            move |_| {
+                                log::info!("Switch account: {}", local_id);
+                                auth.switch_account(&local_id);
+                            }
+                        },
+                        MatButton {
+                            label: account.name.clone(),
+                            outlined: true,
+                            disabled: account.is_active,
+                        }
+                    }
+                }
+            }
+
+            span {
+                onclick: move |_| {
+                    // NOTE: Sign-in routes keep the current accounts
+                    auth.begin_add_account();
+                    navigator.push(Route::Home {});
+                },
+                MatButton {
+                    label: "Add another account",
+                    outlined: true,
+                }
+            }
+        }
+
+        br {}
+    }
+}
+
+fn render_upgrade_account_panel<'a>(
+    cx: Scope<'a>,
+    auth: &'a UseAuth,
+    notifications: &'a UseNotifications,
+    action: &'a UseAsyncAction,
+    email: &'a UseState<String>,
+    password: &'a UseState<String>,
+    confirm_password: &'a UseState<String>,
+) -> Element<'a> {
+    render! {
+        div {
+            outline: "1px solid orange",
+            padding: "10px",
+
+            h2 { "Upgrade your account" }
+
+            div {
+                label {
+                    "You are signed in as a guest. Link an e-mail and password or a Google account to keep your data."
+                }
+            }
+
+            br {}
+
+            div {
+                MatTextField {
+                    label: "E-mail",
+                    value: email.get(),
+                    _oninput: {
+                        to_owned![email];
+                        move |event: String| {
+                            email.set(event)
+                        }
+                    }
+                }
+
+                EmailHint {
+                    email: email.get().clone(),
+                    on_accept: move |suggestion: String| email.set(suggestion),
+                }
+            }
+
+            div {
+                MatTextField {
+                    label: "Password",
+                    value: password.get().clone().replace(|_| true, "*"),
+                    _oninput: {
+                        to_owned![password];
+                        move |event: String| {
+                            password.set(event)
+                        }
+                    }
+                }
+
+                PasswordChecklist {
+                    password: password.get().clone(),
+                    email: Some(email.get().clone()),
+                }
+            }
+
+            div {
+                MatTextField {
+                    label: "Confirm password",
+                    value: confirm_password.get().clone().replace(|_| true, "*"),
+                    _oninput: {
+                        to_owned![confirm_password];
+                        move |event: String| {
+                            confirm_password.set(event)
+                        }
+                    }
+                }
+            }
+
+            div {
+                ActionButton {
+                    label: "Upgrade with e-mail",
+                    action: action,
+                    disabled: !can_link_password(email, password, confirm_password),
+                    onclick: move |_| {
+                        upgrade_with_email_password(cx, auth, notifications, action, email.get().clone(), password.get().clone())
+                    },
+                }
+            }
+
+            if app_config::get().is_enabled(Provider::Google) {
+                render! {
+                    br {}
+
+                    div {
+                        span {
+                            onclick: |_| link_with_google(None),
+                            MatButton {
+                                label: "Upgrade with Google",
+                                outlined: true,
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
 fn render_profile_tab<'a>(
     cx: Scope<'a>,
+    auth: &'a UseAuth,
+    notifications: &'a UseNotifications,
+    action: &'a UseAsyncAction,
     display_name: &'a UseState<String>,
     photo_url: &'a UseState<String>,
     fetch_user_data: &'a UseFuture<Option<UserData>>,
@@ -140,7 +456,7 @@ fn render_profile_tab<'a>(
                         }
                     },
                     | Some(user_data) => {
-                        render_user_data(cx, user_data)
+                        render_user_data(cx, auth, notifications, action, user_data)
                     },
                 }
 
@@ -188,15 +504,12 @@ fn render_profile_tab<'a>(
                 }
 
                 div {
-                    span {
+                    ActionButton {
+                        label: "Update profile",
+                        action: action,
                         onclick: |_| {
-                            update_profile(cx, display_name.get().clone(), photo_url.get().clone());
-                            fetch_user_data.restart();
+                            update_profile(cx, auth, notifications, action, fetch_user_data, display_name.get().clone(), photo_url.get().clone());
                         },
-                        MatButton {
-                            label: "Update profile",
-                            outlined: true,
-                        }
                     }
                 }
             }
@@ -204,9 +517,18 @@ fn render_profile_tab<'a>(
     }
 }
 
+// NOTE: Takes the hooks of the dashboard
+#[allow(clippy::too_many_arguments)]
 fn render_credentials_tab<'a>(
     cx: Scope<'a>,
+    auth: &'a UseAuth,
+    notifications: &'a UseNotifications,
+    action: &'a UseAsyncAction,
+    sensitive_operation: &'a UseSensitiveOperation,
+    user_email: Option<String>,
+    has_password_provider: bool,
     email: &'a UseState<String>,
+    current_password: &'a UseState<String>,
     password: &'a UseState<String>,
     confirm_password: &'a UseState<String>,
     link_email: &'a UseState<String>,
@@ -235,52 +557,72 @@ fn render_credentials_tab<'a>(
 
             div
             {
-                span {
-                    onclick: |_| change_email(cx, email.get().clone()),
-                    MatButton {
-                        label: "Change e-mail",
-                        outlined: true,
-                        disabled: email.get().is_empty(),
-                    }
+                ActionButton {
+                    label: "Change e-mail",
+                    action: sensitive_operation.action(),
+                    disabled: !is_valid_email(email.get()),
+                    onclick: {
+                        to_owned![user_email];
+                        move |_| {
+                            sensitive_operation.spawn(cx, SensitiveOperation::ChangeEmail {
+                                email: email.get().clone(),
+                                previous_email: user_email.clone(),
+                            })
+                        }
+                    },
                 }
             }
 
-            br {}
+            if let Some(pending) = sensitive_operation.pending_email_change(user_email.as_deref()) {
+                render! {
+                    div {
+                        outline: "1px solid orange",
+                        padding: "10px",
 
-            div {
-                MatTextField {
-                    label: "Password",
-                    value: password.get().clone().replace(|_| true, "*"),
-                    _oninput: {
-                        to_owned![password];
-                        move |event: String| {
-                            password.set(event)
+                        div {
+                            label {
+                                "Waiting for verification of {pending.email}. Open the link sent to the new address to finish the change."
+                            }
                         }
-                    }
-                }
-            }
 
-            div {
-                MatTextField {
-                    label: "Confirm password",
-                    value: confirm_password.get().clone().replace(|_| true, "*"),
-                    _oninput: {
-                        to_owned![confirm_password];
-                        move |event: String| {
-                            confirm_password.set(event)
+                        div {
+                            label {
+                                "Your current address stays in use until then. If the change was not made by you, use the link sent to your current address to revert it."
+                            }
+                        }
+
+                        div {
+                            ActionButton {
+                                label: "Resend",
+                                action: sensitive_operation.action(),
+                                onclick: move |_| sensitive_operation.spawn(cx, SensitiveOperation::ChangeEmail {
+                                    email: pending.email.clone(),
+                                    previous_email: pending.previous_email.clone(),
+                                }),
+                            }
+
+                            span {
+                                onclick: |_| sensitive_operation.clear_email_change(),
+                                MatButton {
+                                    label: "Dismiss",
+                                    outlined: true,
+                                }
+                            }
                         }
                     }
                 }
             }
 
-            div {
-                span {
-                    onclick: |_| change_password(cx, password.get().clone()),
-                    MatButton {
-                        label: "Change password",
-                        outlined: true,
-                        disabled: password.get().is_empty()
-                            || confirm_password.get().is_empty(),
+            br {}
+
+            if has_password_provider {
+                render_change_password_form(cx, sensitive_operation, user_email.clone(), current_password, password, confirm_password)
+            } else {
+                render! {
+                    div {
+                        label {
+                            "Link an e-mail and password below to sign in with a password."
+                        }
                     }
                 }
             }
@@ -327,39 +669,162 @@ fn render_credentials_tab<'a>(
             }
 
             div {
-                span {
-                    onclick: |_| link_with_email_password(cx, email.get().clone(), password.get().clone()),
-                    MatButton {
-                        label: "Link password",
-                        outlined: true,
-                    }
+                ActionButton {
+                    label: "Link password",
+                    action: action,
+                    disabled: !can_link_password(link_email, link_password, link_confirm_password),
+                    onclick: |_| link_with_email_password(cx, auth, notifications, action, link_email.get().clone(), link_password.get().clone()),
                 }
             }
 
             div {
-                span {
-                    onclick: |_| unlink_provider(cx, ProviderId::Password),
-                    MatButton {
-                        label: "Unlink password",
-                        outlined: true,
+                ActionButton {
+                    label: "Unlink password",
+                    action: action,
+                    onclick: |_| unlink_provider(cx, auth, notifications, action, ProviderId::Password),
+                }
+            }
+
+            if app_config::get().is_enabled(Provider::Google) {
+                render! {
+                    div {
+                        span {
+                            onclick: {
+                                to_owned![user_email];
+                                move |_| link_with_google(user_email.clone())
+                            },
+                            MatButton {
+                                label: "Link Google OAuth",
+                                outlined: true,
+                            }
+                        }
                     }
                 }
             }
 
             div {
-                span {
-                    onclick: |_| unlink_provider(cx, ProviderId::Google),
-                    MatButton {
-                        label: "Unlink Google OAuth",
-                        outlined: true,
+                ActionButton {
+                    label: "Unlink Google OAuth",
+                    action: action,
+                    onclick: |_| unlink_provider(cx, auth, notifications, action, ProviderId::Google),
+                }
+            }
+        }
+    }
+}
+
+fn render_change_password_form<'a>(
+    cx: Scope<'a>,
+    sensitive_operation: &'a UseSensitiveOperation,
+    user_email: Option<String>,
+    current_password: &'a UseState<String>,
+    password: &'a UseState<String>,
+    confirm_password: &'a UseState<String>,
+) -> Element<'a> {
+    let is_mismatched = !confirm_password.get().is_empty()
+        && password.get() != confirm_password.get();
+    let can_change = can_change_password(
+        user_email.as_deref(),
+        current_password,
+        password,
+        confirm_password,
+    );
+
+    render! {
+        div {
+            MatTextField {
+                label: "Current password",
+                value: current_password.get().clone().replace(|_| true, "*"),
+                _oninput: {
+                    to_owned![current_password];
+                    move |event: String| {
+                        current_password.set(event)
+                    }
+                }
+            }
+        }
+
+        div {
+            MatTextField {
+                label: "New password",
+                value: password.get().clone().replace(|_| true, "*"),
+                _oninput: {
+                    to_owned![password];
+                    move |event: String| {
+                        password.set(event)
+                    }
+                }
+            }
+
+            PasswordChecklist {
+                password: password.get().clone(),
+                email: user_email.clone(),
+            }
+        }
+
+        div {
+            MatTextField {
+                label: "Confirm new password",
+                value: confirm_password.get().clone().replace(|_| true, "*"),
+                _oninput: {
+                    to_owned![confirm_password];
+                    move |event: String| {
+                        confirm_password.set(event)
+                    }
+                }
+            }
+
+            if is_mismatched {
+                render! {
+                    div {
+                        color: "red",
+                        label {
+                            "Passwords do not match."
+                        }
                     }
                 }
             }
         }
+
+        div {
+            ActionButton {
+                label: "Change password",
+                action: sensitive_operation.action(),
+                disabled: !can_change,
+                onclick: {
+                    to_owned![user_email];
+                    move |_| {
+                        if let (true, Some(email)) = (can_change, user_email.clone()) {
+                            // NOTE: Prove the current password before the change
+                            sensitive_operation.spawn_with_password(
+                                cx,
+                                email,
+                                current_password.get().clone(),
+                                SensitiveOperation::ChangePassword {
+                                    password: password.get().clone(),
+                                },
+                            );
+                            current_password.set(String::new());
+                            password.set(String::new());
+                            confirm_password.set(String::new());
+                        }
+                    }
+                },
+            }
+        }
     }
 }
 
-fn render_delete_account_tab(cx: Scope) -> Element {
+fn render_delete_account_tab<'a>(
+    cx: Scope<'a>,
+    sensitive_operation: &'a UseSensitiveOperation,
+    user_data: Option<&'a UserData>,
+    delete_confirmation: &'a UseState<String>,
+) -> Element<'a> {
+    let email = user_data.and_then(|user_data| user_data.email.clone());
+    let is_confirmed =
+        is_delete_confirmed(delete_confirmation.get(), email.as_deref());
+
     render! {
         div {
             outline: "1px solid green",
@@ -370,27 +835,80 @@ fn render_delete_account_tab(cx: Scope) -> Element {
             div {
                 color: "red",
                 label {
-                    "Are you sure you want to delete your account?"
+                    "Your account will be deleted permanently. This cannot be undone."
                 }
             }
 
             br {}
 
+            match user_data {
+                | Some(user_data) => render! {
+                    div {
+                        "The following will be lost:"
+                    }
+
+                    ul {
+                        li {
+                            "Profile: "
+                            span { user_data.display_name.clone() }
+                        }
+
+                        li {
+                            "E-mail: "
+                            span { user_data.email.clone().unwrap_or("".to_string()) }
+                        }
+
+                        for provider in user_data.provider_user_info.iter().flatten() {
+                            render! {
+                                li {
+                                    "Linked provider: "
+                                    span { provider.provider_id.clone() }
+                                }
+                            }
+                        }
+                    }
+                },
+                | None => render! {
+                    div {
+                        "Loading account details..."
+                    }
+                },
+            }
+
             div {
-                span {
-                    onclick: |_| delete_account(cx),
-                    MatButton {
-                        label: "Delete Account",
-                        outlined: true,
+                MatTextField {
+                    label: "Type your e-mail or DELETE to confirm",
+                    value: delete_confirmation.get(),
+                    _oninput: {
+                        to_owned![delete_confirmation];
+                        move |event: String| {
+                            delete_confirmation.set(event)
+                        }
                     }
                 }
             }
+
+            br {}
+
+            div {
+                ActionButton {
+                    label: "Delete Account",
+                    action: sensitive_operation.action(),
+                    disabled: !is_confirmed,
+                    onclick: move |_| {
+                        request_account_deletion(cx, sensitive_operation, user_data)
+                    },
+                }
+            }
         }
     }
 }
 
 fn render_user_data<'a>(
     cx: Scope<'a>,
+    auth: &'a UseAuth,
+    notifications: &'a UseNotifications,
+    action: &'a UseAsyncAction,
     user_data: &Option<UserData>,
 ) -> Element<'a> {
     match user_data {
@@ -472,13 +990,11 @@ fn render_user_data<'a>(
             br {}
 
             div {
-                span {
-                    onclick: |_| send_email_verification(cx),
-                    MatButton {
-                        label: "Send email verification",
-                        outlined: true,
-                        disabled: user_data.email_verified.unwrap_or(true),
-                    }
+                ActionButton {
+                    label: "Send email verification",
+                    action: action,
+                    disabled: user_data.email_verified.unwrap_or(true),
+                    onclick: |_| send_email_verification(cx, auth, notifications, action),
                 }
             }
         },
@@ -543,24 +1059,16 @@ fn render_provider_user_info<'a>(
 }
 
 async fn fetch_user_data_helper(
-    auth_option: Option<Session>
+    session: Session
 ) -> Option<(Session, UserData)> {
-    match auth_option {
-        | Some(session) => {
-            log::info!("Get user data");
-            match session.get_user_data().await {
-                | Ok((new_auth, user_data)) => {
-                    log::info!("Get user data success");
-                    Some((new_auth, user_data))
-                },
-                | Err(error) => {
-                    log::error!("Get user data failed: {:?}", error);
-                    None
-                },
-            }
+    log::info!("Get user data");
+    match session.get_user_data().await {
+        | Ok((new_session, user_data)) => {
+            log::info!("Get user data success");
+            Some((new_session, user_data))
         },
-        | None => {
-            log::error!("Auth context is not available");
+        | Err(error) => {
+            log::error!("Get user data failed: {:?}", error);
             None
         },
     }
@@ -568,291 +1076,360 @@ async fn fetch_user_data_helper(
 
 fn redirect_to_home_if_not_logged_in(
     cx: &Scoped<'_>,
-    context: &UseSharedState<Arc<Mutex<ApplicationContext>>>,
+    auth: &UseAuth,
 ) {
     // Setup hooks
-    let context = context.clone();
     let navigation = use_navigator(cx).clone();
 
-    cx.spawn(async move {
-        let context = context.clone();
-        let context = context.read();
-        let context = context.lock().await;
-        if context.auth_session.is_none() {
+    if !auth.is_signed_in() && auth.status() != AuthStatus::Restoring {
+        cx.spawn(async move {
             log::info!("Redirect to home");
             navigation.push(Route::Home {});
-        }
-    });
+        });
+    }
 }
 
-fn send_email_verification(cx: &Scoped<'_>) {
+fn send_email_verification(
+    cx: &Scoped<'_>,
+    auth: &UseAuth,
+    notifications: &UseNotifications,
+    action: &UseAsyncAction,
+) {
     // Setup hooks
-    let context = use_shared_state::<Arc<Mutex<ApplicationContext>>>(cx)
-        .unwrap()
-        .clone();
+    let auth = auth.clone();
+    let notifications = notifications.clone();
 
-    cx.spawn({
+    action.run(cx, {
         async move {
-            let context = context.clone();
-            let context = context.read();
-            let mut context = context.lock().await;
-            if let Some(sessoin) = &context.auth_session {
-                log::info!("Send email verification");
-                match sessoin
-                    .clone()
-                    .send_email_verification(None)
-                    .await
-                {
-                    | Ok(new_session) => {
-                        log::info!("Send email verification success");
-                        context.auth_session = Some(new_session);
-                    },
-                    | Err(error) => {
-                        log::error!(
-                            "Send email verification failed: {:?}",
-                            error
-                        );
-                    },
-                }
+            let snapshot = match auth.snapshot() {
+                | Some(snapshot) => snapshot,
+                | None => return Ok(()),
+            };
+
+            log::info!("Send email verification");
+            match snapshot
+                .session
+                .clone()
+                .send_email_verification(None)
+                .await
+            {
+                | Ok(new_session) => {
+                    log::info!("Send email verification success");
+                    notifications.success("Verification e-mail has been sent.");
+                    auth.update_session(&snapshot, new_session);
+                    Ok(())
+                },
+                | Err(error) => {
+                    log::error!("Send email verification failed: {:?}", error);
+                    let error = ErrorPresentation::from(&error);
+                    notifications.error(error.clone());
+                    Err(error)
+                },
             }
         }
     })
 }
 
-fn change_email(
+fn update_profile(
     cx: &Scoped<'_>,
-    email: String,
+    auth: &UseAuth,
+    notifications: &UseNotifications,
+    action: &UseAsyncAction,
+    fetch_user_data: &UseFuture<Option<UserData>>,
+    display_name: String,
+    photo_url: String,
 ) {
     // Setup hooks
-    let context = use_shared_state::<Arc<Mutex<ApplicationContext>>>(cx)
-        .unwrap()
-        .clone();
+    let auth = auth.clone();
+    let notifications = notifications.clone();
+    let fetch_user_data = fetch_user_data.clone();
 
-    cx.spawn({
+    action.run(cx, {
         async move {
-            let context = context.clone();
-            let context = context.read();
-            let mut context = context.lock().await;
-            if let Some(session) = &context.auth_session {
-                log::info!("Change email");
-                match session
-                    .clone()
-                    .change_email(email, None)
-                    .await
-                {
-                    | Ok(new_session) => {
-                        log::info!("Change email success");
-                        context.auth_session = Some(new_session);
-                    },
-                    | Err(error) => {
-                        log::error!("Change email failed: {:?}", error);
-                    },
-                }
+            let snapshot = match auth.snapshot() {
+                | Some(snapshot) => snapshot,
+                | None => return Ok(()),
+            };
+
+            log::info!("Update profile");
+            match snapshot
+                .session
+                .clone()
+                .update_profile(Some(display_name), Some(photo_url))
+                .await
+            {
+                | Ok(new_session) => {
+                    log::info!("Update profile success");
+                    notifications.success("Profile has been updated.");
+                    auth.update_session(&snapshot, new_session);
+                    fetch_user_data.restart();
+                    Ok(())
+                },
+                | Err(error) => {
+                    log::error!("Update profile failed: {:?}", error);
+                    let error = ErrorPresentation::from(&error);
+                    notifications.error(error.clone());
+                    Err(error)
+                },
             }
         }
     })
 }
 
-fn change_password(
+fn is_delete_confirmed(
+    confirmation: &str,
+    email: Option<&str>,
+) -> bool {
+    let confirmation = confirmation.trim();
+
+    confirmation == "DELETE"
+        || email
+            .map(|email| confirmation.eq_ignore_ascii_case(email))
+            .unwrap_or(false)
+}
+
+fn is_recent_login(user_data: &UserData) -> bool {
+    user_data
+        .last_login_at
+        .parse::<f64>()
+        .map(|last_login_at| {
+            js_sys::Date::now() - last_login_at < RECENT_LOGIN_MILLISECONDS
+        })
+        .unwrap_or(false)
+}
+
+fn request_account_deletion(
     cx: &Scoped<'_>,
-    password: String,
+    sensitive_operation: &UseSensitiveOperation,
+    user_data: Option<&UserData>,
+) {
+    log::info!("Request account deletion");
+    // NOTE: Ask for the credential up front instead of failing
+    if user_data
+        .map(is_recent_login)
+        .unwrap_or(false)
+    {
+        sensitive_operation.spawn(cx, SensitiveOperation::DeleteAccount);
+    } else {
+        sensitive_operation
+            .request_reauthentication(SensitiveOperation::DeleteAccount);
+    }
+}
+
+fn sign_out(
+    cx: &Scoped<'_>,
+    auth: &UseAuth,
+    notifications: &UseNotifications,
 ) {
     // Setup hooks
-    let context = use_shared_state::<Arc<Mutex<ApplicationContext>>>(cx)
-        .unwrap()
-        .clone();
+    let navigation = use_navigator(cx).clone();
 
-    cx.spawn({
-        async move {
-            let context = context.clone();
-            let context = context.read();
-            let mut context = context.lock().await;
-            if let Some(session) = &context.auth_session {
-                log::info!("Change password");
-                match session
-                    .clone()
-                    .change_password(password)
-                    .await
-                {
-                    | Ok(new_session) => {
-                        log::info!("Change password success");
-                        context.auth_session = Some(new_session);
-                    },
-                    | Err(error) => {
-                        log::error!("Change password failed: {:?}", error);
-                    },
-                }
-            }
-        }
-    })
+    log::info!("Sign out");
+    // NOTE: Reset auth session, another account becomes active if any
+    auth.sign_out();
+    notifications.info("Signed out.");
+    // NOTE: Navigate to home
+    if !auth.is_signed_in() {
+        navigation.push(Route::Home {});
+    }
 }
 
-fn update_profile(
+fn link_with_email_password(
     cx: &Scoped<'_>,
-    display_name: String,
-    photo_url: String,
+    auth: &UseAuth,
+    notifications: &UseNotifications,
+    action: &UseAsyncAction,
+    email: String,
+    password: String,
 ) {
     // Setup hooks
-    let context = use_shared_state::<Arc<Mutex<ApplicationContext>>>(cx)
-        .unwrap()
-        .clone();
+    let auth = auth.clone();
+    let notifications = notifications.clone();
 
-    cx.spawn({
+    action.run(cx, {
         async move {
-            let context = context.clone();
-            let context = context.read();
-            let mut context = context.lock().await;
-            if let Some(session) = &context.auth_session {
-                log::info!("Update profile");
-                match session
-                    .clone()
-                    .update_profile(Some(display_name), Some(photo_url))
-                    .await
-                {
-                    | Ok(new_session) => {
-                        log::info!("Update profile success");
-                        context.auth_session = Some(new_session);
-                    },
-                    | Err(error) => {
-                        log::error!("Update profile failed: {:?}", error);
-                    },
-                }
+            let snapshot = match auth.snapshot() {
+                | Some(snapshot) => snapshot,
+                | None => return Ok(()),
+            };
+
+            log::info!("Link with email password");
+            match snapshot
+                .session
+                .clone()
+                .link_with_email_password(email, password)
+                .await
+            {
+                | Ok(new_session) => {
+                    log::info!("Link with email password success");
+                    notifications.success("E-mail and password have been linked.");
+                    auth.update_session(&snapshot, new_session);
+                    Ok(())
+                },
+                | Err(error) => {
+                    log::error!("Link with email password failed: {:?}", error);
+                    let error = ErrorPresentation::from(&error);
+                    notifications.error(error.clone());
+                    Err(error)
+                },
             }
         }
     })
 }
 
-fn sign_out(cx: &Scoped<'_>) {
-    // Setup hooks
-    let context: UseSharedState<Arc<Mutex<ApplicationContext>>> =
-        use_shared_state::<Arc<Mutex<ApplicationContext>>>(cx)
-            .unwrap()
-            .clone();
-    let navigation = use_navigator(cx).clone();
+fn can_change_password(
+    email: Option<&str>,
+    current_password: &UseState<String>,
+    password: &UseState<String>,
+    confirm_password: &UseState<String>,
+) -> bool {
+    let current_password = current_password.get();
+    let password = password.get();
+    let confirm_password = confirm_password.get();
+
+    email.is_some()
+        && !current_password.is_empty()
+        && PasswordPolicy::default().is_valid(password, email)
+        && password == confirm_password
+        && password != current_password
+}
 
-    cx.spawn({
-        async move {
-            let context = context.clone();
-            let context = context.read();
-            let mut context = context.lock().await;
-
-            log::info!("Sign out");
-            // NOTE: Reset auth session
-            context.auth_session = None;
-            // NOTE: Navigate to home
-            navigation.push(Route::Home {});
-        }
-    });
+fn can_link_password(
+    email: &UseState<String>,
+    password: &UseState<String>,
+    confirm_password: &UseState<String>,
+) -> bool {
+    let email = email.get();
+    let password = password.get();
+    let confirm_password = confirm_password.get();
+
+    is_valid_email(email)
+        && PasswordPolicy::default().is_valid(password, Some(email))
+        && password == confirm_password
 }
 
-fn delete_account(cx: &Scoped<'_>) {
+fn upgrade_with_email_password(
+    cx: &Scoped<'_>,
+    auth: &UseAuth,
+    notifications: &UseNotifications,
+    action: &UseAsyncAction,
+    email: String,
+    password: String,
+) {
     // Setup hooks
-    let context = use_shared_state::<Arc<Mutex<ApplicationContext>>>(cx)
-        .unwrap()
-        .clone();
-    let navigation = use_navigator(cx).clone();
+    let auth = auth.clone();
+    let notifications = notifications.clone();
+    let navigator = use_navigator(cx).clone();
 
-    cx.spawn({
+    action.run(cx, {
         async move {
-            let context = context.clone();
-            let context = context.read();
-            let mut context = context.lock().await;
-            if let Some(session) = &context.auth_session {
-                log::info!("Delete account");
-                match session
+            if let Some(snapshot) = auth.snapshot() {
+                log::info!("Upgrade anonymous account with email password");
+                // NOTE: Linking keeps the local ID of the anonymous user
+                match snapshot
+                    .session
                     .clone()
-                    .delete_account()
+                    .link_with_email_password(email, password)
                     .await
                 {
-                    | Ok(_) => {
-                        log::info!("Delete account success");
-                        // NOTE: Reset auth context
-                        context.auth_session = None;
-                        // NOTE: Navigate to home
-                        navigation.push(Route::Home {});
+                    | Ok(new_session) => {
+                        log::info!("Upgrade anonymous account success");
+                        notifications.success(
+                            "Your account has been upgraded.",
+                        );
+                        auth.update_session(&snapshot, new_session);
                     },
                     | Err(error) => {
-                        log::error!("Delete account failed: {:?}", error);
+                        log::error!(
+                            "Upgrade anonymous account failed: {:?}",
+                            error
+                        );
+                        let error = ErrorPresentation::from(&error);
+                        notifications.error(error.clone());
+                        return Err(error);
                     },
                 }
             }
-        }
-    });
-}
-
-fn link_with_email_password(
-    cx: &Scoped<'_>,
-    email: String,
-    password: String,
-) {
-    // Setup hooks
-    let context = use_shared_state::<Arc<Mutex<ApplicationContext>>>(cx)
-        .unwrap()
-        .clone();
 
-    cx.spawn({
-        async move {
-            let context = context.clone();
-            let context = context.read();
-            let mut context = context.lock().await;
-            if let Some(session) = &context.auth_session {
-                log::info!("Link with email password");
-                match session
+            // NOTE: The linked e-mail has to be verified as a new sign up
+            if let Some(snapshot) = auth.snapshot() {
+                log::info!("Send email verification");
+                match snapshot
+                    .session
                     .clone()
-                    .link_with_email_password(email, password)
+                    .send_email_verification(None)
                     .await
                 {
                     | Ok(new_session) => {
-                        log::info!("Link with email password success");
-                        context.auth_session = Some(new_session);
+                        log::info!("Send email verification success");
+                        auth.update_session(&snapshot, new_session);
                     },
                     | Err(error) => {
                         log::error!(
-                            "Link with email password failed: {:?}",
+                            "Send email verification failed: {:?}",
                             error
                         );
+                        notifications.error((&error).into());
                     },
                 }
+                navigator.push(Route::VerifyEmail {});
             }
+
+            Ok(())
         }
     });
 }
 
+fn link_with_google(email: Option<String>) {
+    log::info!("Link with Google");
+    if let Err(error) = authorize_with_google(OAuthIntent::Link, email) {
+        log::error!("Failed to authorize with Google: {:?}", error);
+    }
+}
+
 fn unlink_provider(
     cx: &Scoped<'_>,
+    auth: &UseAuth,
+    notifications: &UseNotifications,
+    action: &UseAsyncAction,
     provider_id: ProviderId,
 ) {
     // Setup hooks
-    let context = use_shared_state::<Arc<Mutex<ApplicationContext>>>(cx)
-        .unwrap()
-        .clone();
+    let auth = auth.clone();
+    let notifications = notifications.clone();
 
-    cx.spawn({
+    action.run(cx, {
         async move {
-            let context = context.clone();
-            let context = context.read();
-            let mut context = context.lock().await;
-            if let Some(session) = &context.auth_session {
-                log::info!("Unlink provider: {}", provider_id);
-                match session
-                    .clone()
-                    .unlink_provider(
-                        [provider_id]
-                            .iter()
-                            .cloned()
-                            .collect(),
-                    )
-                    .await
-                {
-                    | Ok(new_session) => {
-                        log::info!("Unlink provider success");
-                        context.auth_session = Some(new_session);
-                    },
-                    | Err(error) => {
-                        log::error!("Unlink provider failed: {:?}", error);
-                    },
-                }
+            let snapshot = match auth.snapshot() {
+                | Some(snapshot) => snapshot,
+                | None => return Ok(()),
+            };
+
+            log::info!("Unlink provider: {}", provider_id);
+            match snapshot
+                .session
+                .clone()
+                .unlink_provider(
+                    [provider_id]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                )
+                .await
+            {
+                | Ok(new_session) => {
+                    log::info!("Unlink provider success");
+                    notifications.success("Provider has been unlinked.");
+                    auth.update_session(&snapshot, new_session);
+                    Ok(())
+                },
+                | Err(error) => {
+                    log::error!("Unlink provider failed: {:?}", error);
+                    let error = ErrorPresentation::from(&error);
+                    notifications.error(error.clone());
+                    Err(error)
+                },
             }
         }
-    });
+    })
 }
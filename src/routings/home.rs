@@ -1,78 +1,138 @@
-use std::sync::Arc;
-
-use async_std::sync::Mutex;
 use dioxus::prelude::{
-    component, dioxus_elements, fc_to_builder, render, use_shared_state,
-    Element, Scope, Scoped, UseSharedState,
+    component, dioxus_elements, fc_to_builder, render, Element, IntoDynNode,
+    Scope, Scoped,
 };
 use dioxus_router::hooks::use_navigator;
 use material_dioxus::MatButton;
 
-use crate::{application_context::ApplicationContext, routings::route::Route};
+use crate::{
+    app_config::{self, Provider},
+    auth::{use_auth, UseAuth},
+    routings::route::Route,
+};
 
 #[allow(non_snake_case)]
 #[component(no_case_check)]
 pub(crate) fn Home(cx: Scope) -> Element {
     // Setup hooks
-    let context =
-        use_shared_state::<Arc<Mutex<ApplicationContext>>>(cx).unwrap();
+    let auth = use_auth(cx);
     let navigator = use_navigator(cx);
+    let config = app_config::get();
 
-    redirect_to_dashboard_if_logged_in(cx, context);
+    redirect_to_dashboard_if_logged_in(cx, auth);
 
     render! {
         h1 { "Home" }
 
-        div {
-            span {
-                onclick: |_| {
-                    navigator.push(Route::SignUp { });
-                },
-                MatButton {
-                    label: "Sign up with email",
-                    outlined: true,
+        if auth.is_adding_account() {
+            render! {
+                div {
+                    label {
+                        "Sign in to another account. The current accounts stay signed in."
+                    }
                 }
+
+                div {
+                    span {
+                        onclick: |_| {
+                            auth.cancel_add_account();
+                            navigator.push(Route::Dashboard { });
+                        },
+                        MatButton {
+                            label: "Back to dashboard",
+                            outlined: true,
+                        }
+                    }
+                }
+
+                br {}
             }
         }
 
-        br {}
-
-        div {
-            span {
-                onclick: |_| {
-                    navigator.push(Route::SignIn { });
-                },
-                MatButton {
-                    label: "Sign in with email",
-                    outlined: true,
+        if config.is_enabled(Provider::Password) {
+            render! {
+                div {
+                    span {
+                        onclick: |_| {
+                            navigator.push(Route::SignUp { });
+                        },
+                        MatButton {
+                            label: "Sign up with email",
+                            outlined: true,
+                        }
+                    }
                 }
+
+                br {}
             }
         }
 
-        br {}
+        if config.is_enabled(Provider::Password) {
+            render! {
+                div {
+                    span {
+                        onclick: |_| {
+                            navigator.push(Route::SignIn { });
+                        },
+                        MatButton {
+                            label: "Sign in with email",
+                            outlined: true,
+                        }
+                    }
+                }
+
+                br {}
+            }
+        }
 
-        div {
-            span {
-                onclick: |_| {
-                    navigator.push(Route::SignInWithOAuth { });
-                },
-                MatButton {
-                    label: "Sign in with OAuth",
-                    outlined: true,
+        if config.is_enabled(Provider::EmailLink) {
+            render! {
+                div {
+                    span {
+                        onclick: |_| {
+                            navigator.push(Route::SignInWithEmailLink { });
+                        },
+                        MatButton {
+                            label: "Sign in with email link",
+                            outlined: true,
+                        }
+                    }
                 }
+
+                br {}
             }
         }
 
-        br {}
+        if config.is_enabled(Provider::Google) {
+            render! {
+                div {
+                    span {
+                        onclick: |_| {
+                            navigator.push(Route::SignInWithOAuth { });
+                        },
+                        MatButton {
+                            label: "Sign in with OAuth",
+                            outlined: true,
+                        }
+                    }
+                }
 
-        div {
-            span {
-                onclick: |_| {
-                    navigator.push(Route::SignInAnonymously { });
-                },
-                MatButton {
-                    label: "Sign in anonymously",
-                    outlined: true,
+                br {}
+            }
+        }
+
+        if config.is_enabled(Provider::Anonymous) {
+            render! {
+                div {
+                    span {
+                        onclick: |_| {
+                            navigator.push(Route::SignInAnonymously { });
+                        },
+                        MatButton {
+                            label: "Sign in anonymously",
+                            outlined: true,
+                        }
+                    }
                 }
             }
         }
@@ -81,19 +141,16 @@ pub(crate) fn Home(cx: Scope) -> Element {
 
 fn redirect_to_dashboard_if_logged_in(
     cx: &Scoped<'_>,
-    context: &UseSharedState<Arc<Mutex<ApplicationContext>>>,
+    auth: &UseAuth,
 ) {
     // Setup hooks
-    let context = context.clone();
     let navigation = use_navigator(cx).clone();
 
-    cx.spawn(async move {
-        let context = context.clone();
-        let context = context.read();
-        let context = context.lock().await;
-        if context.auth_session.is_some() {
+    // NOTE: Stay while adding another account
+    if auth.is_signed_in() && !auth.is_adding_account() {
+        cx.spawn(async move {
             log::info!("Redirect to dashboard");
             navigation.push(Route::Dashboard {});
-        }
-    });
+        });
+    }
 }
@@ -1,83 +1,275 @@
-use async_std::sync::Mutex;
-use std::sync::Arc;
 use std::{collections::HashMap, fmt::Display};
 
 use dioxus::prelude::{
-    component, dioxus_elements, render, use_shared_state, Element, Props, Scope,
+    component, dioxus_elements, fc_to_builder, render, Element, IntoDynNode,
+    Props, Scope,
 };
 use dioxus_router::prelude::{use_navigator, FromQuery};
 use fars::{data::IdpPostBody, Config, Session};
-use google_oauth_rs::api::exchange_access_token::{
-    ExchangeAccessTokenRequestParameters, GrandType,
+use material_dioxus::MatButton;
+use google_oauth_rs::api::{
+    exchange_access_token::{ExchangeAccessTokenRequestParameters, GrandType},
+    revoke_token::RevokeTokenRequestParameters,
 };
+use serde::{Deserialize, Serialize};
 
 use crate::{
-    application_context::ApplicationContext, generated::dotenv,
-    routings::route::Route,
+    app_config,
+    auth::{local_id_of, use_auth},
+    generated::dotenv::BUILD_CONFIG,
+    error_presentation::{
+        ErrorMessage, ErrorPresentation, ErrorView, Severity,
+    },
+    identity_toolkit::{idp, TokenSession},
+    notification::use_notifications,
+    one_shot::{use_one_shot, OneShotStatus},
+    routings::{
+        route::Route,
+        sign_in_oauth::{requested_redirect_uri, verify_state, OAuthIntent},
+    },
+    storage::{
+        self, StorageKind, CONSUMED_GOOGLE_CODE_KEY, GOOGLE_TOKEN_KEY,
+        PENDING_OPERATION_KEY,
+    },
 };
 
+/// Handles the redirect from the Google authorization server.
 #[allow(non_snake_case)]
 #[component(no_case_check)]
 pub(crate) fn OAuthGoogle(
+    cx: Scope,
+    query: GoogleCallbackQuery,
+) -> Element {
+    match query {
+        | GoogleCallbackQuery::Authorized(response) => render! {
+            GoogleAuthorizationCode {
+                query: response.clone(),
+            }
+        },
+        | GoogleCallbackQuery::Failed(error) => render! {
+            GoogleAuthorizationError {
+                query: error.clone(),
+            }
+        },
+    }
+}
+
+#[allow(non_snake_case)]
+#[component(no_case_check)]
+fn GoogleAuthorizationCode(
     cx: Scope,
     query: RedirectToAuthServerResponseQuery,
 ) -> Element {
     // Setup hooks
-    let context =
-        use_shared_state::<Arc<Mutex<ApplicationContext>>>(cx).unwrap();
+    let auth = use_auth(cx);
+    let notifications = use_notifications(cx);
     let navigator = use_navigator(cx);
 
-    let sign_in = move |cx: &Scope<'_, OAuthGoogleProps>| {
-        log::info!("Sign in with Google");
+    let intent = OAuthIntent::from_state(query.state.as_deref());
 
-        let context = context.clone();
+    let status = use_one_shot(cx, || {
+        let auth = auth.clone();
+        let notifications = notifications.clone();
         let navigator = navigator.clone();
         let code = query.code.clone();
+        let query_state = query.state.clone();
+
+        async move {
+            log::info!("Sign in with Google: {:?}", intent);
+            // NOTE: Reject callbacks that this browser did not request
+            if !verify_state(query_state.as_deref()) {
+                log::error!("OAuth state does not match");
+                return Err(ErrorPresentation::new(
+                    ErrorMessage::OAuthFailed,
+                    Severity::Error,
+                    "State does not match the request".to_string(),
+                ));
+            }
 
-        cx.spawn(async move {
-            let context = context.clone();
-            let context = context.read();
-            let mut context = context.lock().await;
-            match sign_in_with_google(context.auth_config.clone(), code).await {
+            consume_authorization_code(&code)?;
+
+            if intent == OAuthIntent::Link {
+                let snapshot = match auth.snapshot() {
+                    | Some(snapshot) => snapshot,
+                    | None => {
+                        log::error!("Not signed in to link Google");
+                        navigator.push(Route::SignIn {});
+                        return Ok(());
+                    },
+                };
+
+                return match link_with_google(snapshot.session.clone(), code)
+                    .await
+                {
+                    | Ok(session) => {
+                        log::info!("Link with Google success");
+                        auth.update_session(&snapshot, session);
+                        notifications.success("Google account has been linked.");
+                        navigator.push(Route::Dashboard {});
+                        Ok(())
+                    },
+                    | Err(link_error) => {
+                        log::error!(
+                            "Error to link with Google: {:?}",
+                            link_error,
+                        );
+                        Err(present_link_error(&link_error))
+                    },
+                };
+            }
+
+            // NOTE: login_hint does not force the account to choose
+            let reauthenticating_id = match intent {
+                | OAuthIntent::Reauthenticate => auth.active_account_id(),
+                | _ => None,
+            };
+
+            auth.begin_sign_in();
+            match sign_in_with_google(auth.config(), code).await {
+                | Ok(session)
+                    if reauthenticating_id.is_some()
+                        && local_id_of(&session.session)
+                            != reauthenticating_id =>
+                {
+                    log::error!("Reauthenticated with another Google account");
+                    storage::remove(StorageKind::Session, PENDING_OPERATION_KEY);
+                    auth.fail_sign_in();
+                    Err(ErrorPresentation::new(
+                        ErrorMessage::AccountMismatch,
+                        Severity::Error,
+                        "Signed in account differs from the active one"
+                            .to_string(),
+                    ))
+                },
                 | Ok(session) => {
                     log::info!("Sign in with Google success");
-                    context.auth_session = Some(session);
+                    auth.complete_sign_in(
+                        session.session,
+                        Some(session.refresh_token),
+                    );
                     navigator.push(Route::Dashboard {});
+                    Ok(())
                 },
-                | Err(error) => {
+                | Err(sign_in_error) => {
                     log::error!(
                         "Error to exchange access token: {:?}",
-                        error,
+                        sign_in_error,
                     );
+                    auth.fail_sign_in();
+                    Err(ErrorPresentation::from(&sign_in_error))
                 },
             }
-        });
-    };
-
-    sign_in(&cx);
+        }
+    });
 
     render! {
-        h1 { "Signing in with Google..." }
+        match status.get() {
+            | OneShotStatus::Failed(error) if intent == OAuthIntent::Link => render! {
+                h1 { "Error to link Google account" }
+
+                ErrorView {
+                    error: error.clone(),
+                }
+
+                div {
+                    span {
+                        onclick: move |_| {
+                            navigator.push(Route::Dashboard {});
+                        },
+                        MatButton {
+                            label: "Back to dashboard",
+                            outlined: true,
+                        }
+                    }
+                }
+            },
+            | OneShotStatus::Failed(error) => render! {
+                h1 { "Error to sign in with Google" }
+
+                ErrorView {
+                    error: error.clone(),
+                }
+
+                div {
+                    span {
+                        onclick: move |_| {
+                            navigator.push(Route::SignInWithOAuth {});
+                        },
+                        MatButton {
+                            label: "Try again",
+                            outlined: true,
+                        }
+                    }
+                }
+            },
+            | OneShotStatus::Succeeded => render! {
+                h1 { "Done. Redirecting..." }
+            },
+            | OneShotStatus::InProgress if intent == OAuthIntent::Link => render! {
+                h1 { "Linking Google account..." }
+            },
+            | OneShotStatus::InProgress => render! {
+                h1 { "Signing in with Google..." }
+            },
+        }
     }
 }
 
 #[allow(non_snake_case)]
 #[component(no_case_check)]
-pub(crate) fn OAuthGoogleError(
+fn GoogleAuthorizationError(
     cx: Scope,
-    error: RedirectToAuthServerResponseErrorQuery,
+    query: RedirectToAuthServerResponseErrorQuery,
 ) -> Element {
-    log::info!(
-        "Redirect error OAuth with Google: error: {:?}",
-        error,
-    );
+    // Setup hooks
+    let navigator = use_navigator(cx);
+
+    let intent = OAuthIntent::from_state(query.state.as_deref());
+    let error = ErrorPresentation::from(query);
+
+    // NOTE: Forget the nonce of the failed request
+    cx.use_hook(|| {
+        log::info!("Redirect error OAuth with Google: {:?}", query);
+        if !verify_state(query.state.as_deref()) {
+            log::warn!("OAuth state does not match");
+        }
+    });
+
+    let (title, next_label, next_route) = match intent {
+        | OAuthIntent::SignIn => (
+            "Error to sign in with Google",
+            "Try again",
+            Route::SignInWithOAuth {},
+        ),
+        | OAuthIntent::Reauthenticate | OAuthIntent::Link => (
+            "Error to authorize with Google",
+            "Back to dashboard",
+            Route::Dashboard {},
+        ),
+    };
 
     render! {
-        h1 { "Error to sign in with Google" }
+        h1 { title }
+
+        ErrorView {
+            error: error,
+        }
+
+        div {
+            span {
+                onclick: move |_| {
+                    navigator.push(next_route.clone());
+                },
+                MatButton {
+                    label: next_label,
+                    outlined: true,
+                }
+            }
+        }
     }
 }
 
-fn parse_query_str(query: &str) -> HashMap<String, String> {
+pub(crate) fn parse_query_str(query: &str) -> HashMap<String, String> {
     query
         .split('&')
         .filter_map(|part| {
@@ -157,9 +349,44 @@ impl FromQuery for RedirectToAuthServerResponseQuery {
     }
 }
 
+/// The query of the Google callback, which is either a code or an error.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum GoogleCallbackQuery {
+    Authorized(RedirectToAuthServerResponseQuery),
+    Failed(RedirectToAuthServerResponseErrorQuery),
+}
+
+impl Display for GoogleCallbackQuery {
+    fn fmt(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        match self {
+            | GoogleCallbackQuery::Authorized(query) => write!(f, "{}", query),
+            | GoogleCallbackQuery::Failed(query) => write!(f, "{}", query),
+        }
+    }
+}
+
+impl FromQuery for GoogleCallbackQuery {
+    fn from_query(query: &str) -> Self {
+        // NOTE: The authorization server sends `error` instead of `code`
+        if parse_query_str(query).contains_key("error") {
+            GoogleCallbackQuery::Failed(
+                RedirectToAuthServerResponseErrorQuery::from_query(query),
+            )
+        } else {
+            GoogleCallbackQuery::Authorized(
+                RedirectToAuthServerResponseQuery::from_query(query),
+            )
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub(crate) struct RedirectToAuthServerResponseErrorQuery {
     pub error: String,
+    pub state: Option<String>,
 }
 
 impl Display for RedirectToAuthServerResponseErrorQuery {
@@ -172,6 +399,11 @@ impl Display for RedirectToAuthServerResponseErrorQuery {
         query.push_str("error=");
         query.push_str(&self.error);
 
+        if let Some(state) = &self.state {
+            query.push_str("&state=");
+            query.push_str(state);
+        }
+
         write!(f, "{}", query)
     }
 }
@@ -184,22 +416,91 @@ impl FromQuery for RedirectToAuthServerResponseErrorQuery {
                 .get("error")
                 .cloned()
                 .unwrap_or_default(),
+            state: params.get("state").cloned(),
         }
     }
 }
 
-async fn sign_in_with_google(
-    auth_config: Config,
+#[derive(Serialize, Deserialize)]
+struct StoredGoogleToken {
+    access_token: String,
+    refresh_token: Option<String>,
+}
+
+impl From<&RedirectToAuthServerResponseErrorQuery> for ErrorPresentation {
+    fn from(query: &RedirectToAuthServerResponseErrorQuery) -> Self {
+        match query.error.as_str() {
+            | "access_denied" => Self::new(
+                ErrorMessage::OAuthDenied,
+                Severity::Info,
+                query.to_string(),
+            ),
+            | _ => Self::new(
+                ErrorMessage::OAuthFailed,
+                Severity::Error,
+                query.to_string(),
+            ),
+        }
+    }
+}
+
+/// Remembers the authorization code so that a duplicated callback,
+/// e.g. by reloading the page, does not exchange it again.
+fn consume_authorization_code(code: &str) -> Result<(), ErrorPresentation> {
+    if code.is_empty() {
+        return Err(ErrorPresentation::new(
+            ErrorMessage::OAuthFailed,
+            Severity::Error,
+            "Authorization code is missing".to_string(),
+        ));
+    }
+
+    let consumed = storage::load::<String>(
+        StorageKind::Session,
+        CONSUMED_GOOGLE_CODE_KEY,
+    );
+    if consumed.as_deref() == Some(code) {
+        log::warn!("Authorization code has already been used");
+        return Err(ErrorPresentation::new(
+            ErrorMessage::OAuthCodeExpired,
+            Severity::Warning,
+            "Authorization code has already been used".to_string(),
+        ));
+    }
+
+    storage::save(
+        StorageKind::Session,
+        CONSUMED_GOOGLE_CODE_KEY,
+        &code.to_string(),
+    );
+    Ok(())
+}
+
+async fn exchange_google_id_token(
     auth_code: String,
-) -> anyhow::Result<Session> {
+    redirect_uri: String,
+) -> anyhow::Result<String> {
     let client = reqwest::ClientBuilder::new().build()?;
+    let client_id = app_config::get()
+        .google_client_id
+        .clone();
+
+    // NOTE: The compiled secret belongs to the compiled client ID only,
+    // a client ID of config.json has to be a public client
+    let client_secret = if client_id == BUILD_CONFIG.google_client_id {
+        BUILD_CONFIG
+            .google_client_secret
+            .to_string()
+    } else {
+        String::new()
+    };
 
     let request_parameter = ExchangeAccessTokenRequestParameters {
-        client_id: dotenv::GOOGLE_CLIENT_ID.to_string(),
-        client_secret: dotenv::GOOGLE_CLIENT_SECRET.to_string(),
+        client_id,
+        client_secret,
         code: auth_code,
         grant_type: GrandType::AuthorizationCode,
-        redirect_uri: "http://localhost:8080/auth/google-callback".to_string(),
+        redirect_uri,
     };
 
     let token_response =
@@ -211,16 +512,97 @@ async fn sign_in_with_google(
 
     log::info!("Exchange access token success");
 
-    let session = auth_config
-        .sign_in_with_oauth_credential(
-            "http://localhost:8080/auth/google-callback".to_string(),
+    // NOTE: Kept only to revoke the grant when the account is deleted
+    storage::save(
+        StorageKind::Session,
+        GOOGLE_TOKEN_KEY,
+        &StoredGoogleToken {
+            access_token: token_response.access_token.clone(),
+            refresh_token: token_response.refresh_token.clone(),
+        },
+    );
+
+    Ok(token_response.id_token)
+}
+
+async fn sign_in_with_google(
+    auth_config: Config,
+    auth_code: String,
+) -> anyhow::Result<TokenSession> {
+    let redirect_uri = requested_redirect_uri();
+    let id_token =
+        exchange_google_id_token(auth_code, redirect_uri.clone()).await?;
+
+    let session =
+        idp::sign_in_with_google(&auth_config, redirect_uri, id_token).await?;
+
+    log::info!("Sign in with OAuth credential success");
+
+    Ok(session)
+}
+
+async fn link_with_google(
+    session: Session,
+    auth_code: String,
+) -> anyhow::Result<Session> {
+    let redirect_uri = requested_redirect_uri();
+    let id_token =
+        exchange_google_id_token(auth_code, redirect_uri.clone()).await?;
+
+    let session = session
+        .link_with_oauth_credential(
+            redirect_uri,
             IdpPostBody::Google {
-                id_token: token_response.id_token,
+                id_token,
             },
         )
         .await?;
 
-    log::info!("Sign in with OAuth credential success");
+    log::info!("Link with OAuth credential success");
 
     Ok(session)
 }
+
+fn present_link_error(error: &anyhow::Error) -> ErrorPresentation {
+    let presentation = ErrorPresentation::from(error);
+    match presentation.message {
+        // NOTE: The Google e-mail belongs to another account
+        | ErrorMessage::EmailExists => ErrorPresentation::new(
+            ErrorMessage::CredentialAlreadyInUse,
+            Severity::Warning,
+            presentation.detail,
+        ),
+        | _ => presentation,
+    }
+}
+
+/// Revokes the Google grant of the stored token if any.
+pub(crate) async fn revoke_stored_google_token() {
+    let stored_token = match storage::take::<StoredGoogleToken>(
+        StorageKind::Session,
+        GOOGLE_TOKEN_KEY,
+    ) {
+        | Some(stored_token) => stored_token,
+        | None => return,
+    };
+
+    // NOTE: Revoking the refresh token also revokes the access token
+    let token = stored_token
+        .refresh_token
+        .unwrap_or(stored_token.access_token);
+
+    let client = reqwest::Client::new();
+    match google_oauth_rs::api::revoke_token::revoke_token(
+        &client,
+        RevokeTokenRequestParameters {
+            token,
+        },
+    )
+    .await
+    {
+        | Ok(_) => log::info!("Revoke Google token success"),
+        | Err(error) => {
+            log::error!("Revoke Google token failed: {:?}", error)
+        },
+    }
+}
@@ -1,18 +1,15 @@
-use async_std::sync::Mutex;
-use std::sync::Arc;
-
-use dioxus::{
-    hooks::UseSharedState,
-    prelude::{
-        component, dioxus_elements, fc_to_builder, render, to_owned,
-        use_shared_state, use_state, Element, GlobalAttributes, IntoDynNode,
-        Scope, Scoped, UseState,
-    },
+use dioxus::prelude::{
+    component, dioxus_elements, fc_to_builder, render, to_owned, use_state,
+    Element, IntoDynNode, Scope, Scoped, UseState,
 };
 use dioxus_router::hooks::use_navigator;
 use material_dioxus::{button::MatButton, text_inputs::MatTextField};
 
-use crate::application_context::ApplicationContext;
+use crate::async_action::{use_async_action, ActionButton, UseAsyncAction};
+use crate::auth::{use_auth, UseAuth};
+use crate::credential::is_valid_email;
+use crate::email_hint::EmailHint;
+use crate::error_presentation::{ErrorPresentation, ErrorView};
 
 use super::route::Route;
 
@@ -20,10 +17,9 @@ use super::route::Route;
 #[component(no_case_check)]
 pub(crate) fn ResetPassword(cx: Scope) -> Element {
     // Setup hooks
-    let context =
-        use_shared_state::<Arc<Mutex<ApplicationContext>>>(cx).unwrap();
+    let auth = use_auth(cx);
     let email = use_state(cx, String::new);
-    let error_message = use_state::<Option<String>>(cx, || None);
+    let send_action = use_async_action(cx);
     let navigator = use_navigator(cx);
 
     render! {
@@ -40,34 +36,31 @@ pub(crate) fn ResetPassword(cx: Scope) -> Element {
                     }
                 }
             }
+
+            EmailHint {
+                email: email.get().clone(),
+                on_accept: move |suggestion: String| email.set(suggestion),
+            }
         }
 
         div {
-            span {
+            ActionButton {
+                label: "Send password reset email",
+                action: send_action,
+                disabled: !can_send(email),
                 onclick: |_| {
-                    if can_send(email)
-                    {
-                        send_send_password_reset_email(cx, context, email.get().clone(), error_message)
-                    }
+                    send_send_password_reset_email(cx, auth, send_action, email.get().clone())
                 },
-                MatButton {
-                    label: "Send password reset email",
-                    outlined: true,
-                    disabled: !can_send(email),
-                }
             }
         }
 
         br {}
 
         div {
-            if let Some(error_message) = error_message.get() {
+            if let Some(error_message) = send_action.error() {
                 render! {
-                    div {
-                        color: "red",
-                        label {
-                            error_message.as_str(),
-                        }
+                    ErrorView {
+                        error: error_message.clone(),
                     }
 
                     br {}
@@ -90,63 +83,35 @@ pub(crate) fn ResetPassword(cx: Scope) -> Element {
 }
 
 fn can_send(email: &UseState<String>) -> bool {
-    !email.get().is_empty()
+    is_valid_email(email.get())
 }
 
 fn send_send_password_reset_email(
     cx: &Scoped<'_>,
-    context: &UseSharedState<Arc<Mutex<ApplicationContext>>>,
+    auth: &UseAuth,
+    action: &UseAsyncAction,
     email: String,
-    error_message: &UseState<Option<String>>,
 ) {
     // Setup hooks
-    let context = context.clone();
-    let error_message = error_message.clone();
+    let auth = auth.clone();
     let navigation = use_navigator(cx).clone();
 
-    cx.spawn({
+    action.run(cx, {
         async move {
             log::info!("Send password reset email: {:?}", email);
-            error_message.set(None);
-            let context = context.clone();
-            let context = context.read();
-            let context = context.lock().await;
-            match context
-                .auth_config
+            match auth
+                .config()
                 .send_reset_password_email(email, None)
                 .await
             {
                 | Ok(_) => {
                     log::info!("Send password reset email success");
-                    error_message.set(None);
                     navigation.push(Route::SignIn {});
+                    Ok(())
                 },
                 | Err(error) => {
                     log::error!("Sign up failed: {:?}", error);
-                    match error {
-                        | fars::error::Error::ApiError {
-                            status_code: _,
-                            error_code,
-                            response: _,
-                        } => match error_code {
-                            | fars::error::CommonErrorCode::EmailNotFound => {
-                                error_message.set(Some(
-                                    "Error: E-mail address not found."
-                                        .to_string(),
-                                ));
-                            },
-                            | _ => {
-                                error_message.set(Some(
-                                    "Error: Internal error.".to_string(),
-                                ));
-                            },
-                        },
-                        | _ => {
-                            error_message.set(Some(
-                                "Error: Internal error.".to_string(),
-                            ));
-                        },
-                    }
+                    Err(ErrorPresentation::from(&error))
                 },
             }
         }
@@ -2,18 +2,21 @@ use dioxus::prelude::{fc_to_builder, render};
 use dioxus_router::prelude::{Routable, ToRouteSegments};
 
 use super::{
+    auth_action::{AuthAction, AuthActionQuery},
     dashboard::Dashboard,
     home::Home,
     not_found::NotFound,
-    oauth_google::{
-        OAuthGoogle, OAuthGoogleError, RedirectToAuthServerResponseErrorQuery,
-        RedirectToAuthServerResponseQuery,
-    },
+    oauth_google::{GoogleCallbackQuery, OAuthGoogle},
     reset_password::ResetPassword,
     sign_in::SignIn,
     sign_in_anonymously::SignInAnonymously,
+    sign_in_email_link::{
+        EmailLinkQuery, FinishEmailLinkSignIn, SignInWithEmailLink,
+    },
+    sign_in_mfa::SignInSecondFactor,
     sign_in_oauth::SignInWithOAuth,
     sign_up::SignUp,
+    verify_email::VerifyEmail,
 };
 
 #[rustfmt::skip]
@@ -25,20 +28,30 @@ pub(crate) enum Route {
     SignUp {},
     #[route("/signin")]
     SignIn {},
+    #[route("/signin/mfa")]
+    SignInSecondFactor {},
     #[route("/signin/oauth")]
     SignInWithOAuth {},
     #[route("/signin/anonymous")]
     SignInAnonymously {},
+    #[route("/signin/email_link")]
+    SignInWithEmailLink {},
+    #[route("/signin/email_link/finish?:query")]
+    FinishEmailLinkSignIn {
+        query: EmailLinkQuery,
+    },
     #[route("/auth/google-callback?:query")]
     OAuthGoogle {
-        query: RedirectToAuthServerResponseQuery,
+        query: GoogleCallbackQuery,
     },
-    #[route("/auth/google-callback?:error")]
-    OAuthGoogleError {
-        error: RedirectToAuthServerResponseErrorQuery,
+    #[route("/auth/action?:query")]
+    AuthAction {
+        query: AuthActionQuery,
     },
     #[route("/reset_password")]
     ResetPassword {},
+    #[route("/verify_email")]
+    VerifyEmail {},
     #[route("/dashboard")]
     Dashboard {},
 
@@ -1,28 +1,54 @@
-use async_std::sync::Mutex;
-use dioxus::hooks::UseSharedState;
-use std::sync::Arc;
+use std::time::Duration;
 
 use dioxus::prelude::{
-    component, dioxus_elements, fc_to_builder, render, to_owned,
-    use_shared_state, use_state, Element, GlobalAttributes, IntoDynNode, Scope,
-    Scoped, UseState,
+    component, dioxus_elements, fc_to_builder, render, to_owned, use_future,
+    use_state, Element, IntoDynNode, Scope, Scoped, UseState,
 };
 use dioxus_router::{components::Link, hooks::use_navigator};
 use material_dioxus::{MatButton, MatTextField};
 
-use crate::application_context::ApplicationContext;
+use crate::application_context::AuthStatus;
+use crate::async_action::{use_async_action, ActionButton, UseAsyncAction};
+use crate::auth::{use_auth, UseAuth};
+use crate::email_hint::EmailHint;
+use crate::error_presentation::{ErrorPresentation, ErrorView};
+use crate::identity_toolkit::mfa::{
+    sign_in_with_email_password, PasswordSignIn,
+};
 use crate::routings::route::Route;
+use crate::storage::{self, StorageKind, MFA_SIGN_IN_KEY};
+use crate::throttle;
 
 #[allow(non_snake_case)]
 #[component(no_case_check)]
 pub(crate) fn SignIn(cx: Scope) -> Element {
     // Setup hooks
-    let context =
-        use_shared_state::<Arc<Mutex<ApplicationContext>>>(cx).unwrap();
+    let auth = use_auth(cx);
     let email = use_state(cx, String::new);
     let password = use_state(cx, String::new);
-    let error_message = use_state::<Option<String>>(cx, || None);
+    let sign_in_action = use_async_action(cx);
     let navigator = use_navigator(cx);
+    let cooldown = use_state(cx, || 0_u64);
+
+    // NOTE: Count down the cooldown of the typed e-mail
+    use_future(cx, (), {
+        let email = email.clone();
+        let cooldown = cooldown.clone();
+        move |_| async move {
+            loop {
+                async_std::task::sleep(Duration::from_secs(1)).await;
+                let remaining = throttle::remaining_cooldown(&email.current());
+                if *cooldown.current() != remaining {
+                    cooldown.set(remaining);
+                }
+            }
+        }
+    });
+
+    let sign_in_label = match cooldown.get() {
+        | 0 => "Sign In".to_string(),
+        | seconds => format!("Sign In ({}s)", seconds),
+    };
 
     render! {
         h1 { "Sign in" }
@@ -32,12 +58,18 @@ pub(crate) fn SignIn(cx: Scope) -> Element {
                 label: "E-mail",
                 value: email.get(),
                 _oninput: {
-                    to_owned![email];
+                    to_owned![email, cooldown];
                     move |event :String| {
+                        cooldown.set(throttle::remaining_cooldown(&event));
                         email.set(event)
                     }
                 }
             }
+
+            EmailHint {
+                email: email.get().clone(),
+                on_accept: move |suggestion: String| email.set(suggestion),
+            }
         }
 
         div {
@@ -54,17 +86,22 @@ pub(crate) fn SignIn(cx: Scope) -> Element {
         }
 
         div {
-            span {
+            ActionButton {
+                label: sign_in_label,
+                action: sign_in_action,
+                disabled: !can_sign_in(auth, email, password, cooldown),
                 onclick: |_| {
-                    if can_sign_in(email, password)
-                    {
-                        sign_in(cx, context, email.get().clone(), password.get().clone(), error_message)
-                    }
+                    sign_in(cx, auth, sign_in_action, email.get().clone(), password.get().clone(), cooldown)
                 },
-                MatButton {
-                    label: "Sign In",
-                    outlined: true,
-                    disabled: !can_sign_in(email, password),
+            }
+        }
+
+        if *cooldown.get() > 0 {
+            render! {
+                div {
+                    label {
+                        "Too many failed attempts. Please wait {cooldown.get()} seconds before trying again."
+                    }
                 }
             }
         }
@@ -72,13 +109,10 @@ pub(crate) fn SignIn(cx: Scope) -> Element {
         br {}
 
         div {
-            if let Some(error_message) = error_message.get() {
+            if let Some(error_message) = sign_in_action.error() {
                 render! {
-                    div {
-                        color: "red",
-                        label {
-                            error_message.as_str(),
-                        }
+                    ErrorView {
+                        error: error_message.clone(),
                     }
 
                     br {}
@@ -133,61 +167,63 @@ pub(crate) fn SignIn(cx: Scope) -> Element {
 }
 
 fn can_sign_in(
+    auth: &UseAuth,
     email: &UseState<String>,
     password: &UseState<String>,
+    cooldown: &UseState<u64>,
 ) -> bool {
-    !email.get().is_empty() && !password.get().is_empty()
+    !email.get().is_empty()
+        && !password.get().is_empty()
+        && *cooldown.get() == 0
+        && auth.status() != AuthStatus::SigningIn
 }
 
 fn sign_in(
     cx: &Scoped<'_>,
-    context: &UseSharedState<Arc<Mutex<ApplicationContext>>>,
+    auth: &UseAuth,
+    action: &UseAsyncAction,
     email: String,
     password: String,
-    error_message: &UseState<Option<String>>,
+    cooldown: &UseState<u64>,
 ) {
-    let context = context.clone();
+    let auth = auth.clone();
     let navigator = use_navigator(cx).clone();
-    let error_message = error_message.clone();
+    let cooldown = cooldown.clone();
 
-    cx.spawn({
+    action.run(cx, {
         async move {
             log::info!("Sign in: {:?}", email);
-            error_message.set(None);
-            let context = context.clone();
-            let context = context.read();
-            let mut context = context.lock().await;
-            match context.auth_config.sign_in_with_email_password(
-                email,
+            auth.begin_sign_in();
+            match sign_in_with_email_password(
+                &auth.config(),
+                email.clone(),
                 password,
             ).await {
-                | Ok(session) => {
+                | Ok(PasswordSignIn::Completed(session)) => {
                     log::info!("Sign in success");
-                    context.auth_session = Some(session);
+                    throttle::record_success(&email);
+                    auth.complete_sign_in(session.session, Some(session.refresh_token));
                     navigator.push(Route::Dashboard {});
+                    Ok(())
+                },
+                | Ok(PasswordSignIn::MfaRequired(pending)) => {
+                    log::info!("Sign in requires second factor");
+                    throttle::record_success(&email);
+                    auth.fail_sign_in();
+                    storage::save(
+                        StorageKind::Session,
+                        MFA_SIGN_IN_KEY,
+                        &pending,
+                    );
+                    navigator.push(Route::SignInSecondFactor {});
+                    Ok(())
                 },
                 | Err(error) => {
                     log::error!("Sign in failed: {:?}", error);
-                    match error {
-                        | fars::error::Error::ApiError {
-                            status_code: _,
-                            error_code,
-                            response: _,
-                        } => match error_code {
-                            | fars::error::CommonErrorCode::InvalidLoginCredentials => {
-                                error_message.set(Some("Error: Invalid email or password.".to_string()));
-                            },
-                            | fars::error::CommonErrorCode::UserDisabled => {
-                                error_message.set(Some("Error: User disabled.".to_string()));
-                            },
-                            | _ => {
-                                error_message.set(Some("Error: Internal error.".to_string()));
-                            },
-                        },
-                        | _ => {
-                            error_message.set(Some("Error: Internal error.".to_string()));
-                        },
-                    }
+                    let error = ErrorPresentation::from(&error);
+                    cooldown.set(throttle::record_failure(&email, &error));
+                    auth.fail_sign_in();
+                    Err(error)
                 },
             }
         }
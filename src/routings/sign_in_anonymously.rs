@@ -1,59 +1,75 @@
-use async_std::sync::Mutex;
-use std::sync::Arc;
-
 use dioxus::prelude::{
-    component, dioxus_elements, fc_to_builder, render, use_shared_state,
-    Element, Scope,
+    component, dioxus_elements, fc_to_builder, render, Element, IntoDynNode,
+    Scope,
 };
 use dioxus_router::hooks::use_navigator;
 use material_dioxus::MatButton;
 
-use crate::{application_context::ApplicationContext, routings::route::Route};
+use crate::{
+    auth::use_auth,
+    error_presentation::{ErrorPresentation, ErrorView},
+    identity_toolkit::sign_up::sign_in_anonymously,
+    one_shot::{use_one_shot, OneShotStatus},
+    routings::route::Route,
+};
 
 #[allow(non_snake_case)]
 #[component(no_case_check)]
 pub(crate) fn SignInAnonymously(cx: Scope) -> Element {
     // Setup hooks
-    let context =
-        use_shared_state::<Arc<Mutex<ApplicationContext>>>(cx).unwrap();
+    let auth = use_auth(cx);
     let navigator = use_navigator(cx);
 
-    let sign_in = move |cx: &Scope<'_>| {
-        log::info!("Sign in anonymously");
-
-        let context = context.clone();
+    let status = use_one_shot(cx, || {
+        let auth = auth.clone();
         let navigator = navigator.clone();
 
-        cx.spawn(async move {
-            let context = context.clone();
-            let context = context.read();
-            let mut context = context.lock().await;
-            match context
-                .auth_config
-                .clone()
-                .sign_in_anonymously()
-                .await
-            {
+        async move {
+            log::info!("Sign in anonymously");
+            auth.begin_sign_in();
+            match sign_in_anonymously(&auth.config()).await {
                 | Ok(session) => {
                     log::info!("Sign in anonymously success");
-                    context.auth_session = Some(session);
+                    auth.complete_sign_in(
+                        session.session,
+                        Some(session.refresh_token),
+                    );
                     navigator.push(Route::Dashboard {});
+                    Ok(())
                 },
-                | Err(error) => {
+                | Err(sign_in_error) => {
                     log::error!(
                         "Error to sign in anonymously: {:?}",
-                        error,
+                        sign_in_error,
                     );
+                    auth.fail_sign_in();
+                    Err(ErrorPresentation::from(&sign_in_error))
                 },
             }
-        });
-    };
-
-    sign_in(&cx);
+        }
+    });
 
     render! {
         h1 { "Sign in anonymously" }
 
+        match status.get() {
+            | OneShotStatus::InProgress => render! {
+                div {
+                    "Signing in..."
+                }
+            },
+            | OneShotStatus::Succeeded => render! {
+                div {
+                    "Signed in. Redirecting..."
+                }
+            },
+            | OneShotStatus::Failed(error) => render! {
+                ErrorView {
+                    error: error.clone(),
+                }
+            },
+        }
+
         br {}
 
         div {
@@ -0,0 +1,362 @@
+use std::fmt::Display;
+
+use dioxus::prelude::{
+    component, dioxus_elements, fc_to_builder, render, to_owned, use_state,
+    Element, IntoDynNode, Props, Scope, ScopeState, Scoped, UseState,
+};
+use dioxus_router::{
+    hooks::use_navigator,
+    prelude::{FromQuery, Navigator},
+};
+use material_dioxus::{button::MatButton, text_inputs::MatTextField};
+
+use crate::{
+    async_action::{use_async_action, ActionButton, UseAsyncAction},
+    auth::{use_auth, UseAuth},
+    credential::is_valid_email,
+    email_hint::EmailHint,
+    error_presentation::{
+        ErrorMessage, ErrorPresentation, ErrorView, Severity,
+    },
+    identity_toolkit::email_link::{
+        send_sign_in_link, sign_in_with_email_link,
+    },
+    notification::{use_notifications, UseNotifications},
+    routings::{oauth_google::parse_query_str, route::Route},
+    storage::{self, StorageKind, EMAIL_FOR_SIGN_IN_KEY},
+};
+
+/// The mode of an e-mail link to sign in.
+pub(crate) const SIGN_IN_MODE: &str = "signIn";
+
+#[allow(non_snake_case)]
+#[component(no_case_check)]
+pub(crate) fn SignInWithEmailLink(cx: Scope) -> Element {
+    // Setup hooks
+    let email = use_state(cx, String::new);
+    let sent_to = use_state::<Option<String>>(cx, || None);
+    let send_action = use_async_action(cx);
+    let notifications = use_notifications(cx);
+    let navigator = use_navigator(cx);
+
+    render! {
+        h1 { "Sign in with e-mail link" }
+
+        div {
+            MatTextField {
+                label: "E-mail",
+                value: email.get(),
+                _oninput: {
+                    to_owned![email];
+                    move |event :String| {
+                        email.set(event)
+                    }
+                }
+            }
+
+            EmailHint {
+                email: email.get().clone(),
+                on_accept: move |suggestion: String| email.set(suggestion),
+            }
+        }
+
+        div {
+            ActionButton {
+                label: "Send sign-in link",
+                action: send_action,
+                disabled: !is_valid_email(email.get()),
+                onclick: |_| {
+                    send_link(cx, notifications, send_action, email.get().clone(), sent_to)
+                },
+            }
+        }
+
+        br {}
+
+        if let Some(sent_to) = sent_to.get() {
+            render! {
+                div {
+                    label {
+                        "Open the link sent to {sent_to} on this device to sign in."
+                    }
+                }
+
+                br {}
+            }
+        }
+
+        div {
+            if let Some(error_message) = send_action.error() {
+                render! {
+                    ErrorView {
+                        error: error_message.clone(),
+                    }
+
+                    br {}
+                }
+            }
+        }
+
+        div {
+            span {
+                onclick: move |_| {
+                    navigator.push(Route::Home { });
+                },
+                MatButton {
+                    label: "Back to home",
+                    outlined: true,
+                }
+            }
+        }
+    }
+}
+
+#[allow(non_snake_case)]
+#[component(no_case_check)]
+pub(crate) fn FinishEmailLinkSignIn(
+    cx: Scope,
+    query: EmailLinkQuery,
+) -> Element {
+    // Setup hooks
+    let auth = use_auth(cx);
+    let email = use_state(cx, String::new);
+    let finish_action = use_async_action(cx);
+    let navigator = use_navigator(cx);
+    let stored_email = cx.use_hook(|| {
+        storage::load::<String>(StorageKind::Local, EMAIL_FOR_SIGN_IN_KEY)
+    });
+    let is_valid_link =
+        !query.oob_code.is_empty() && query.mode == SIGN_IN_MODE;
+
+    // NOTE: Finish at once when the link is opened on the same device
+    cx.use_hook(|| {
+        if let Some(stored_email) = stored_email
+            .clone()
+            .filter(|_| is_valid_link)
+        {
+            finish_sign_in(
+                cx,
+                auth,
+                navigator,
+                finish_action,
+                stored_email,
+                query.oob_code.clone(),
+            );
+        }
+    });
+
+    if !is_valid_link {
+        return render! {
+            h1 { "Sign in with e-mail link" }
+
+            ErrorView {
+                error: ErrorPresentation::new(
+                    ErrorMessage::InvalidActionCode,
+                    Severity::Warning,
+                    query.to_string(),
+                ),
+            }
+        };
+    }
+
+    render! {
+        h1 { "Sign in with e-mail link" }
+
+        if finish_action.is_pending() {
+            render! {
+                div {
+                    "Signing in..."
+                }
+            }
+        }
+
+        // NOTE: The link was opened on another device or browser
+        if stored_email.is_none() {
+            render! {
+                div {
+                    label {
+                        "Please enter the e-mail address the link was sent to."
+                    }
+                }
+
+                div {
+                    MatTextField {
+                        label: "E-mail",
+                        value: email.get(),
+                        _oninput: {
+                            to_owned![email];
+                            move |event :String| {
+                                email.set(event)
+                            }
+                        }
+                    }
+                }
+
+                div {
+                    ActionButton {
+                        label: "Sign in",
+                        action: finish_action,
+                        disabled: !is_valid_email(email.get()),
+                        onclick: |_| {
+                            finish_sign_in(cx, auth, navigator, finish_action, email.get().clone(), query.oob_code.clone())
+                        },
+                    }
+                }
+
+                br {}
+            }
+        }
+
+        div {
+            if let Some(error_message) = finish_action.error() {
+                render! {
+                    ErrorView {
+                        error: error_message.clone(),
+                    }
+
+                    br {}
+                }
+            }
+        }
+
+        div {
+            span {
+                onclick: move |_| {
+                    navigator.push(Route::SignInWithEmailLink { });
+                },
+                MatButton {
+                    label: "Send a new link",
+                    outlined: true,
+                }
+            }
+        }
+    }
+}
+
+fn send_link(
+    cx: &Scoped<'_>,
+    notifications: &UseNotifications,
+    action: &UseAsyncAction,
+    email: String,
+    sent_to: &UseState<Option<String>>,
+) {
+    // Setup hooks
+    let notifications = notifications.clone();
+    let sent_to = sent_to.clone();
+
+    action.run(cx, {
+        async move {
+            log::info!("Send sign-in link: {:?}", email);
+            match send_sign_in_link(email.clone(), continue_url()).await {
+                | Ok(_) => {
+                    log::info!("Send sign-in link success");
+                    // NOTE: Remembered to finish without asking again
+                    storage::save(
+                        StorageKind::Local,
+                        EMAIL_FOR_SIGN_IN_KEY,
+                        &email,
+                    );
+                    notifications.success("Sign-in link has been sent.");
+                    sent_to.set(Some(email));
+                    Ok(())
+                },
+                | Err(error) => {
+                    log::error!("Send sign-in link failed: {:?}", error);
+                    Err(ErrorPresentation::from(&error))
+                },
+            }
+        }
+    })
+}
+
+fn finish_sign_in(
+    cx: &ScopeState,
+    auth: &UseAuth,
+    navigator: &Navigator,
+    action: &UseAsyncAction,
+    email: String,
+    oob_code: String,
+) {
+    // Setup hooks
+    let auth = auth.clone();
+    let navigator = navigator.clone();
+
+    action.run(cx, {
+        async move {
+            log::info!("Sign in with email link: {:?}", email);
+            auth.begin_sign_in();
+            match sign_in_with_email_link(&auth.config(), email, oob_code)
+                .await
+            {
+                | Ok(session) => {
+                    log::info!("Sign in with email link success");
+                    storage::remove(StorageKind::Local, EMAIL_FOR_SIGN_IN_KEY);
+                    auth.complete_sign_in(session.session, Some(session.refresh_token));
+                    navigator.push(Route::Dashboard {});
+                    Ok(())
+                },
+                | Err(error) => {
+                    log::error!("Sign in with email link failed: {:?}", error);
+                    auth.fail_sign_in();
+                    Err(ErrorPresentation::from(&error))
+                },
+            }
+        }
+    })
+}
+
+/// The URL of the finishing route on the current origin.
+fn continue_url() -> String {
+    let origin = web_sys::window()
+        .and_then(|window| window.location().origin().ok())
+        .unwrap_or_default();
+    let route = Route::FinishEmailLinkSignIn {
+        query: EmailLinkQuery::from_query(""),
+    }
+    .to_string();
+    let path = route
+        .split('?')
+        .next()
+        .unwrap_or_default();
+
+    format!("{}{}", origin, path)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct EmailLinkQuery {
+    pub oob_code: String,
+    pub mode: String,
+}
+
+impl Display for EmailLinkQuery {
+    fn fmt(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        let mut query = String::new();
+
+        query.push_str("mode=");
+        query.push_str(&self.mode);
+
+        query.push_str("&oobCode=");
+        query.push_str(&self.oob_code);
+
+        write!(f, "{}", query)
+    }
+}
+
+impl FromQuery for EmailLinkQuery {
+    fn from_query(query: &str) -> Self {
+        let params = parse_query_str(query);
+        EmailLinkQuery {
+            oob_code: params
+                .get("oobCode")
+                .cloned()
+                .unwrap_or_default(),
+            mode: params
+                .get("mode")
+                .cloned()
+                .unwrap_or_default(),
+        }
+    }
+}
@@ -0,0 +1,199 @@
+use dioxus::prelude::{
+    component, dioxus_elements, fc_to_builder, render, to_owned, use_state,
+    Element, IntoDynNode, Scope, ScopeState, UseState,
+};
+use dioxus_router::hooks::use_navigator;
+use material_dioxus::{button::MatButton, text_inputs::MatTextField};
+
+use crate::{
+    application_context::AuthStatus,
+    async_action::{use_async_action, ActionButton, UseAsyncAction},
+    auth::{use_auth, UseAuth},
+    error_presentation::{ErrorPresentation, ErrorView},
+    identity_toolkit::mfa::{finalize_sign_in, PendingMfaSignIn},
+    routings::route::Route,
+    storage::{self, StorageKind, MFA_SIGN_IN_KEY},
+};
+
+#[allow(non_snake_case)]
+#[component(no_case_check)]
+pub(crate) fn SignInSecondFactor(cx: Scope) -> Element {
+    // Setup hooks
+    let auth = use_auth(cx);
+    let code = use_state(cx, String::new);
+    let selected = use_state(cx, || 0_usize);
+    let verify_action = use_async_action(cx);
+    let navigator = use_navigator(cx);
+    let pending = &*cx.use_hook(|| {
+        storage::load::<PendingMfaSignIn>(StorageKind::Session, MFA_SIGN_IN_KEY)
+    });
+
+    let pending = match pending {
+        | Some(pending) => pending,
+        | None => {
+            return render! {
+                h1 { "Two-step verification" }
+
+                div {
+                    "The sign-in has expired. Please sign in again."
+                }
+
+                br {}
+
+                div {
+                    span {
+                        onclick: move |_| {
+                            navigator.push(Route::SignIn { });
+                        },
+                        MatButton {
+                            label: "Back to sign in",
+                            outlined: true,
+                        }
+                    }
+                }
+            };
+        },
+    };
+
+    render! {
+        h1 { "Two-step verification" }
+
+        div {
+            label {
+                "Enter the code from your authenticator app for {pending.email}."
+            }
+        }
+
+        // NOTE: Let the user choose when several apps are enrolled
+        if pending.factors.len() > 1 {
+            render! {
+                div {
+                    for (index, factor) in pending.factors.iter().enumerate() {
+                        render! {
+                            span {
+                                onclick: move |_| selected.set(index),
+                                MatButton {
+                                    label: factor.display_name.clone().unwrap_or("Authenticator app".to_string()),
+                                    outlined: true,
+                                    disabled: *selected.get() == index,
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        div {
+            MatTextField {
+                label: "Verification code",
+                value: code.get(),
+                _oninput: {
+                    to_owned![code];
+                    move |event: String| {
+                        code.set(event)
+                    }
+                }
+            }
+        }
+
+        div {
+            ActionButton {
+                label: "Verify",
+                action: verify_action,
+                disabled: !can_verify(auth, code),
+                onclick: |_| {
+                    verify(cx, auth, verify_action, pending, *selected.get(), code.get().clone())
+                },
+            }
+        }
+
+        br {}
+
+        div {
+            if let Some(error_message) = verify_action.error() {
+                render! {
+                    ErrorView {
+                        error: error_message.clone(),
+                    }
+
+                    br {}
+                }
+            }
+        }
+
+        div {
+            span {
+                onclick: move |_| {
+                    storage::remove(StorageKind::Session, MFA_SIGN_IN_KEY);
+                    navigator.push(Route::SignIn { });
+                },
+                MatButton {
+                    label: "Cancel",
+                    outlined: true,
+                }
+            }
+        }
+    }
+}
+
+fn can_verify(
+    auth: &UseAuth,
+    code: &UseState<String>,
+) -> bool {
+    let code = code.get().trim();
+
+    !code.is_empty()
+        && code
+            .chars()
+            .all(|character| character.is_ascii_digit())
+        && auth.status() != AuthStatus::SigningIn
+}
+
+fn verify(
+    cx: &ScopeState,
+    auth: &UseAuth,
+    action: &UseAsyncAction,
+    pending: &PendingMfaSignIn,
+    selected: usize,
+    code: String,
+) {
+    // Setup hooks
+    let auth = auth.clone();
+    let pending = pending.clone();
+    let navigator = use_navigator(cx).clone();
+
+    let factor = match pending.factors.get(selected) {
+        | Some(factor) => factor.clone(),
+        | None => {
+            log::error!("Second factor is not found: {}", selected);
+            return;
+        },
+    };
+
+    action.run(cx, async move {
+        log::info!("Verify second factor");
+        auth.begin_sign_in();
+        match finalize_sign_in(
+            &auth.config(),
+            &pending,
+            factor.mfa_enrollment_id,
+            code.trim().to_string(),
+        )
+        .await
+        {
+            | Ok(session) => {
+                log::info!("Verify second factor success");
+                storage::remove(StorageKind::Session, MFA_SIGN_IN_KEY);
+                auth.complete_sign_in(session.session, Some(session.refresh_token));
+                navigator.push(Route::Dashboard {});
+                Ok(())
+            },
+            | Err(error) => {
+                log::error!("Verify second factor failed: {:?}", error);
+                auth.fail_sign_in();
+                Err(ErrorPresentation::from(&error))
+            },
+        }
+    });
+}
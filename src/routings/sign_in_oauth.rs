@@ -1,10 +1,18 @@
+use std::fmt::Write as _;
+
 use dioxus::prelude::{
     component, dioxus_elements, fc_to_builder, render, Element, Scope,
 };
-use dioxus_router::prelude::use_navigator;
+use dioxus_router::prelude::{use_navigator, FromQuery};
 use material_dioxus::MatButton;
 
-use crate::routings::route::Route;
+use crate::{
+    generated::dotenv::BUILD_CONFIG,
+    routings::{oauth_google::GoogleCallbackQuery, route::Route},
+    storage::{
+        self, StorageKind, OAUTH_REDIRECT_URI_KEY, OAUTH_STATE_KEY,
+    },
+};
 
 #[allow(non_snake_case)]
 #[component(no_case_check)]
@@ -19,7 +27,7 @@ pub(crate) fn SignInWithOAuth(cx: Scope) -> Element {
             span {
                 onclick: |_| {
                     log::info!("Sign in with Google");
-                    let _ = authorize_with_google();
+                    let _ = authorize_with_google(OAuthIntent::SignIn, None);
                 },
                 MatButton {
                     label: "Sign in with Google",
@@ -44,11 +52,133 @@ pub(crate) fn SignInWithOAuth(cx: Scope) -> Element {
     }
 }
 
-fn authorize_with_google() -> anyhow::Result<()> {
+/// What the Google callback should do with the authorization code.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum OAuthIntent {
+    SignIn,
+    Reauthenticate,
+    Link,
+}
+
+impl OAuthIntent {
+    fn as_str(self) -> &'static str {
+        match self {
+            | OAuthIntent::SignIn => "sign_in",
+            | OAuthIntent::Reauthenticate => "reauthenticate",
+            | OAuthIntent::Link => "link",
+        }
+    }
+
+    /// Builds the `state` parameter as `{intent}:{nonce}` and remembers
+    /// the nonce to verify the callback.
+    fn to_state(self) -> anyhow::Result<String> {
+        let nonce = generate_nonce()?;
+        storage::save(StorageKind::Session, OAUTH_STATE_KEY, &nonce);
+
+        Ok(format!("{}:{}", self.as_str(), nonce))
+    }
+
+    pub(crate) fn from_state(state: Option<&str>) -> Self {
+        let intent = state
+            .and_then(|state| state.split(':').next())
+            .unwrap_or_default();
+
+        match intent {
+            | "reauthenticate" => OAuthIntent::Reauthenticate,
+            | "link" => OAuthIntent::Link,
+            | _ => OAuthIntent::SignIn,
+        }
+    }
+}
+
+/// Checks the `state` of the callback against the nonce of the request.
+///
+/// The nonce is single use, so a replayed callback is rejected.
+pub(crate) fn verify_state(state: Option<&str>) -> bool {
+    let expected =
+        storage::take::<String>(StorageKind::Session, OAUTH_STATE_KEY);
+    let nonce = state
+        .and_then(|state| state.split_once(':'))
+        .map(|(_, nonce)| nonce);
+
+    match (expected, nonce) {
+        | (Some(expected), Some(nonce)) => expected == nonce,
+        | _ => false,
+    }
+}
+
+fn generate_nonce() -> anyhow::Result<String> {
+    let crypto = web_sys::window()
+        .ok_or(anyhow::anyhow!("Failed to get window"))?
+        .crypto()
+        .map_err(|e| anyhow::anyhow!("Failed to get crypto: {:?}", e))?;
+
+    let mut bytes = [0_u8; 16];
+    crypto
+        .get_random_values_with_u8_array(&mut bytes)
+        .map_err(|e| anyhow::anyhow!("Failed to get random values: {:?}", e))?;
+
+    Ok(bytes
+        .iter()
+        .fold(String::new(), |mut nonce, byte| {
+            let _ = write!(nonce, "{:02x}", byte);
+            nonce
+        }))
+}
+
+/// The redirect URI of the Google callback route on the current origin.
+pub(crate) fn google_redirect_uri() -> String {
+    // NOTE: Overridden when the registered URI differs from the origin,
+    // e.g. behind a reverse proxy
+    let origin = match &crate::app_config::get().redirect_origin {
+        | Some(redirect_origin) => redirect_origin.clone(),
+        | None => match web_sys::window()
+            .and_then(|window| window.location().origin().ok())
+        {
+            | Some(origin) => origin,
+            // NOTE: The compiled URI is only a fallback without a window
+            | None => {
+                return BUILD_CONFIG
+                    .google_redirect_uri
+                    .unwrap_or_default()
+                    .to_string();
+            },
+        },
+    };
+    let route = Route::OAuthGoogle {
+        query: GoogleCallbackQuery::from_query(""),
+    }
+    .to_string();
+    let path = route
+        .split('?')
+        .next()
+        .unwrap_or_default();
+
+    format!("{}{}", origin, path)
+}
+
+/// The redirect URI sent with the authorization request, which has to
+/// be sent again to exchange the code.
+pub(crate) fn requested_redirect_uri() -> String {
+    storage::load::<String>(StorageKind::Session, OAUTH_REDIRECT_URI_KEY)
+        .unwrap_or_else(google_redirect_uri)
+}
+
+pub(crate) fn authorize_with_google(
+    intent: OAuthIntent,
+    login_hint: Option<String>,
+) -> anyhow::Result<()> {
     if let Some(window) = web_sys::window() {
+        let redirect_uri = google_redirect_uri();
+        storage::save(
+            StorageKind::Session,
+            OAUTH_REDIRECT_URI_KEY,
+            &redirect_uri,
+        );
+
         let url = google_oauth_rs::api::request_authorization::AuthorizationRequestParameters {
-            client_id: crate::generated::dotenv::GOOGLE_CLIENT_ID.to_string(),
-            redirect_uri: "http://localhost:8080/auth/google-callback".to_string(),
+            client_id: crate::app_config::get().google_client_id.clone(),
+            redirect_uri,
             scope: vec![
                 google_oauth_rs::api::request_authorization::Scope::OpenID,
                 google_oauth_rs::api::request_authorization::Scope::Email,
@@ -56,10 +186,10 @@ fn authorize_with_google() -> anyhow::Result<()> {
             ],
             response_type: google_oauth_rs::api::request_authorization::ResponseType::Code,
             access_type: Some(google_oauth_rs::api::request_authorization::AccessType::Offline),
-            state: Some("state".to_string()), // TODO: Generate a random string
+            state: Some(intent.to_state()?),
             include_granted_scopes: Some(true),
             enable_granular_consent: None,
-            login_hint: None,
+            login_hint,
             prompt: None,
         }.build_redirect_uri()?;
 
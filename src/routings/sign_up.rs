@@ -1,28 +1,30 @@
-use async_std::sync::Mutex;
-use std::sync::Arc;
-
 use dioxus::prelude::{
-    component, dioxus_elements, fc_to_builder, render, to_owned,
-    use_shared_state, use_state, Element, GlobalAttributes, IntoDynNode, Scope,
-    Scoped, UseSharedState, UseState,
+    component, dioxus_elements, fc_to_builder, render, to_owned, use_state,
+    Element, GlobalAttributes, IntoDynNode, Scope, Scoped, UseState,
 };
 use dioxus_router::{components::Link, hooks::use_navigator};
 use material_dioxus::{MatButton, MatTextField};
 
-use crate::application_context::ApplicationContext;
-use crate::credential::{is_valid_email, is_valid_password};
+use crate::async_action::{use_async_action, ActionButton, UseAsyncAction};
+use crate::auth::{use_auth, UseAuth};
+use crate::credential::{is_valid_email, EmailAddress, PasswordPolicy};
+use crate::email_hint::EmailHint;
+use crate::error_presentation::{ErrorPresentation, ErrorView};
+use crate::identity_toolkit::{
+    sign_up::sign_up_with_email_password, TokenSession,
+};
+use crate::password_checklist::PasswordChecklist;
 use crate::routings::route::Route;
 
 #[allow(non_snake_case)]
 #[component(no_case_check)]
 pub(crate) fn SignUp(cx: Scope) -> Element {
     // Setup hooks
-    let context =
-        use_shared_state::<Arc<Mutex<ApplicationContext>>>(cx).unwrap();
+    let auth = use_auth(cx);
     let email = use_state(cx, String::new);
     let password = use_state(cx, String::new);
     let confirm_password = use_state(cx, String::new);
-    let error_message = use_state::<Option<String>>(cx, || None);
+    let sign_up_action = use_async_action(cx);
     let navigator = use_navigator(cx);
 
     render! {
@@ -40,27 +42,9 @@ pub(crate) fn SignUp(cx: Scope) -> Element {
                 }
             }
 
-            if email.get().is_empty() {
-                render! { span {} }
-            }
-            else if is_valid_email(email.get().clone()) {
-                render! {
-                    span {
-                        color: "green",
-                        label {
-                            "✓"
-                        }
-                    }
-                }
-            } else {
-                render! {
-                    span {
-                        color: "red",
-                        label {
-                            " Please enter a valid e-mail address."
-                        }
-                    }
-                }
+            EmailHint {
+                email: email.get().clone(),
+                on_accept: move |suggestion: String| email.set(suggestion),
             }
         }
 
@@ -76,27 +60,9 @@ pub(crate) fn SignUp(cx: Scope) -> Element {
                 }
             }
 
-            if password.get().is_empty() {
-                render! { span {} }
-            }
-            else if is_valid_password(password.get().clone()) {
-                render! {
-                    span {
-                        color: "green",
-                        label {
-                            "✓"
-                        }
-                    }
-                }
-            } else {
-                render! {
-                    span {
-                        color: "red",
-                        label {
-                            " Please enter a valid password more than 6 characters."
-                        }
-                    }
-                }
+            PasswordChecklist {
+                password: password.get().clone(),
+                email: Some(email.get().clone()),
             }
         }
 
@@ -137,31 +103,23 @@ pub(crate) fn SignUp(cx: Scope) -> Element {
         }
 
         div {
-            span {
+            ActionButton {
+                label: "Sign Up",
+                action: sign_up_action,
+                disabled: !can_sign_up(email, password, confirm_password),
                 onclick: move |_| {
-                    if can_sign_up(email, password, confirm_password)
-                    {
-                        sign_up(cx, context, email.get().clone(), password.get().clone(), error_message)
-                    }
+                    sign_up(cx, auth, sign_up_action, email.get().clone(), password.get().clone())
                 },
-                MatButton {
-                    label: "Sign Up",
-                    outlined: true,
-                    disabled: !can_sign_up(email, password, confirm_password),
-                }
             }
         }
 
         br {}
 
         div {
-            if let Some(error_message) = error_message.get() {
+            if let Some(error_message) = sign_up_action.error() {
                 render! {
-                    div {
-                        color: "red",
-                        label {
-                            error_message.as_str(),
-                        }
+                    ErrorView {
+                        error: error_message.clone(),
                     }
 
                     br {}
@@ -209,64 +167,67 @@ fn can_sign_up(
     let password = password.get();
     let confirm_password = confirm_password.get();
 
-    is_valid_email(email.clone())
-        && is_valid_password(password.clone())
+    is_valid_email(email)
+        && PasswordPolicy::default().is_valid(password, Some(email))
         && password == confirm_password
 }
 
 fn sign_up(
     cx: &Scoped<'_>,
-    context: &UseSharedState<Arc<Mutex<ApplicationContext>>>,
+    auth: &UseAuth,
+    action: &UseAsyncAction,
     email: String,
     password: String,
-    error_message: &UseState<Option<String>>,
 ) {
     // Setup hooks
-    let context = context.clone();
+    let auth = auth.clone();
     let navigator = use_navigator(cx).clone();
-    let error_message = error_message.clone();
 
-    cx.spawn(async move {
+    // NOTE: Send internationalized domains in punycode
+    let email = EmailAddress::parse(&email)
+        .map(|address| address.to_ascii())
+        .unwrap_or(email);
+
+    action.run(cx, async move {
         log::info!("Sign up: {:?}", email);
-        error_message.set(None);
-        let context = context.clone();
-        let context = context.read();
-        let mut context = context.lock().await;
-        match context.auth_config.sign_up_with_email_password(
+        auth.begin_sign_in();
+        match sign_up_with_email_password(
+            &auth.config(),
             email,
             password,
         ).await {
-            | Ok(session) => {
+            | Ok(TokenSession {
+                session,
+                refresh_token,
+            }) => {
                 log::info!("Sign up success");
-                context.auth_session = Some(session);
-                navigator.push(Route::Dashboard {});
+                // NOTE: Keep the session on failure, it can be resent later
+                let session = match session
+                    .clone()
+                    .send_email_verification(None)
+                    .await
+                {
+                    | Ok(new_session) => {
+                        log::info!("Send email verification success");
+                        new_session
+                    },
+                    | Err(error) => {
+                        log::error!(
+                            "Send email verification failed: {:?}",
+                            error
+                        );
+                        session
+                    },
+                };
+                auth.complete_sign_in(session, Some(refresh_token));
+                navigator.push(Route::VerifyEmail {});
+                Ok(())
             },
             | Err(error) => {
                 log::error!("Sign up failed: {:?}", error);
-                match error {
-                    | fars::error::Error::ApiError {
-                        status_code: _,
-                        error_code,
-                        response: _,
-                    } => match error_code {
-                        | fars::error::CommonErrorCode::EmailExists => {
-                            error_message.set(Some("Error: E-mail address already exists.".to_string()));
-                        },
-                        | fars::error::CommonErrorCode::OperationNotAllowed(_message) => {
-                            error_message.set(Some("Error: Operation not allowed.".to_string()));
-                        },
-                        | fars::error::CommonErrorCode::TooManyAttemptsTryLater => {
-                            error_message.set(Some("Error: Too many attempts. Please try again later.".to_string()));
-                        },
-                        | _ => {
-                            error_message.set(Some("Error: Internal error.".to_string()));
-                        },
-                    },
-                    | _ => {
-                        error_message.set(Some("Error: Internal error.".to_string()));
-                    },
-                }
+                auth.fail_sign_in();
+                Err(ErrorPresentation::from(&error))
             },
-        };
+        }
     });
 }
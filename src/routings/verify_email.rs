@@ -0,0 +1,172 @@
+use std::time::Duration;
+
+use dioxus::prelude::{
+    component, dioxus_elements, fc_to_builder, render, use_future, use_state,
+    Element, Scope, Scoped, UseState,
+};
+use dioxus_router::hooks::use_navigator;
+use material_dioxus::MatButton;
+
+use crate::{
+    async_action::{use_async_action, ActionButton, UseAsyncAction},
+    auth::{use_auth, UseAuth},
+    error_presentation::ErrorPresentation,
+    notification::{use_notifications, UseNotifications},
+    routings::route::Route,
+};
+
+const RESEND_COOLDOWN_SECONDS: u64 = 60;
+const POLLING_INTERVAL: Duration = Duration::from_secs(5);
+
+#[allow(non_snake_case)]
+#[component(no_case_check)]
+pub(crate) fn VerifyEmail(cx: Scope) -> Element {
+    // Setup hooks
+    let auth = use_auth(cx);
+    let notifications = use_notifications(cx);
+    let navigator = use_navigator(cx);
+    let resend_action = use_async_action(cx);
+    // NOTE: Starts after a successful resend, since sending at sign up
+    // may have failed
+    let cooldown = use_state(cx, || 0_u64);
+
+    use_future(cx, (), {
+        let cooldown = cooldown.clone();
+        move |_| async move {
+            loop {
+                async_std::task::sleep(Duration::from_secs(1)).await;
+                cooldown.modify(|seconds| seconds.saturating_sub(1));
+            }
+        }
+    });
+
+    use_future(cx, (), {
+        let auth = auth.clone();
+        let navigator = navigator.clone();
+        move |_| async move {
+            loop {
+                async_std::task::sleep(POLLING_INTERVAL).await;
+                let snapshot = match auth.snapshot() {
+                    | Some(snapshot) => snapshot,
+                    | None => {
+                        log::info!("Redirect to home");
+                        navigator.push(Route::Home {});
+                        break;
+                    },
+                };
+
+                match snapshot
+                    .session
+                    .clone()
+                    .get_user_data()
+                    .await
+                {
+                    | Ok((new_session, user_data)) => {
+                        auth.update_session(&snapshot, new_session);
+                        if user_data
+                            .email_verified
+                            .unwrap_or(false)
+                        {
+                            log::info!("E-mail has been verified");
+                            navigator.push(Route::Dashboard {});
+                            break;
+                        }
+                    },
+                    | Err(error) => {
+                        log::error!("Get user data failed: {:?}", error);
+                    },
+                }
+            }
+        }
+    });
+
+    let resend_label = match cooldown.get() {
+        | 0 => "Resend verification e-mail".to_string(),
+        | seconds => format!("Resend in {} s", seconds),
+    };
+
+    render! {
+        h1 { "Verify your e-mail" }
+
+        div {
+            label {
+                "We have sent a verification link to your e-mail address. "
+                "Please open the link to continue."
+            }
+        }
+
+        br {}
+
+        div {
+            ActionButton {
+                label: resend_label,
+                action: resend_action,
+                disabled: *cooldown.get() > 0,
+                onclick: move |_| {
+                    resend_email_verification(cx, auth, notifications, resend_action, cooldown)
+                },
+            }
+        }
+
+        br {}
+
+        div {
+            span {
+                onclick: move |_| {
+                    log::info!("Sign out");
+                    auth.sign_out();
+                    navigator.push(Route::Home {});
+                },
+                MatButton {
+                    label: "Sign out",
+                    outlined: true,
+                }
+            }
+        }
+    }
+}
+
+fn resend_email_verification(
+    cx: &Scoped<'_>,
+    auth: &UseAuth,
+    notifications: &UseNotifications,
+    action: &UseAsyncAction,
+    cooldown: &UseState<u64>,
+) {
+    // Setup hooks
+    let auth = auth.clone();
+    let notifications = notifications.clone();
+    let cooldown = cooldown.clone();
+
+    action.run(cx, async move {
+        let snapshot = match auth.snapshot() {
+            | Some(snapshot) => snapshot,
+            | None => return Ok(()),
+        };
+
+        log::info!("Resend email verification");
+        match snapshot
+            .session
+            .clone()
+            .send_email_verification(None)
+            .await
+        {
+            | Ok(new_session) => {
+                log::info!("Resend email verification success");
+                notifications.success("Verification e-mail has been sent.");
+                auth.update_session(&snapshot, new_session);
+                cooldown.set(RESEND_COOLDOWN_SECONDS);
+                Ok(())
+            },
+            | Err(error) => {
+                log::error!(
+                    "Resend email verification failed: {:?}",
+                    error
+                );
+                let error = ErrorPresentation::from(&error);
+                notifications.error(error.clone());
+                Err(error)
+            },
+        }
+    });
+}
@@ -0,0 +1,123 @@
+use serde::{de::DeserializeOwned, Serialize};
+use web_sys::Storage;
+
+pub(crate) const PENDING_OPERATION_KEY: &str = "pending_sensitive_operation";
+pub(crate) const GOOGLE_TOKEN_KEY: &str = "google_oauth_token";
+pub(crate) const EMAIL_FOR_SIGN_IN_KEY: &str = "email_for_sign_in";
+pub(crate) const PENDING_EMAIL_CHANGE_KEY: &str = "pending_email_change";
+pub(crate) const SIGN_IN_ATTEMPTS_KEY: &str = "sign_in_attempts";
+pub(crate) const MFA_SIGN_IN_KEY: &str = "pending_mfa_sign_in";
+pub(crate) const ACCOUNTS_KEY: &str = "accounts";
+pub(crate) const CONSUMED_GOOGLE_CODE_KEY: &str = "consumed_google_code";
+pub(crate) const OAUTH_STATE_KEY: &str = "oauth_state";
+pub(crate) const OAUTH_REDIRECT_URI_KEY: &str = "oauth_redirect_uri";
+
+// NOTE: Keys that belong to the signed-in account
+const ACCOUNT_KEYS: &[(StorageKind, &str)] = &[
+    (StorageKind::Session, PENDING_OPERATION_KEY),
+    (StorageKind::Session, GOOGLE_TOKEN_KEY),
+];
+
+// NOTE: Keys that are stored per account by `account_key`
+const PER_ACCOUNT_KEYS: &[(StorageKind, &str)] =
+    &[(StorageKind::Local, PENDING_EMAIL_CHANGE_KEY)];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum StorageKind {
+    // NOTE: Cleared when the tab is closed
+    Session,
+    Local,
+}
+
+impl StorageKind {
+    fn storage(&self) -> Option<Storage> {
+        let window = web_sys::window()?;
+        let storage = match self {
+            | StorageKind::Session => window.session_storage(),
+            | StorageKind::Local => window.local_storage(),
+        };
+
+        storage.ok().flatten()
+    }
+}
+
+pub(crate) fn load<T: DeserializeOwned>(
+    kind: StorageKind,
+    key: &str,
+) -> Option<T> {
+    let value = kind
+        .storage()?
+        .get_item(key)
+        .ok()
+        .flatten()?;
+
+    match serde_json::from_str(&value) {
+        | Ok(value) => Some(value),
+        | Err(error) => {
+            log::error!("Failed to parse stored {}: {:?}", key, error);
+            None
+        },
+    }
+}
+
+pub(crate) fn save<T: Serialize>(
+    kind: StorageKind,
+    key: &str,
+    value: &T,
+) {
+    let storage = match kind.storage() {
+        | Some(storage) => storage,
+        | None => {
+            log::error!("Storage is not available");
+            return;
+        },
+    };
+
+    match serde_json::to_string(value) {
+        | Ok(value) => {
+            if let Err(error) = storage.set_item(key, &value) {
+                log::error!("Failed to store {}: {:?}", key, error);
+            }
+        },
+        | Err(error) => {
+            log::error!("Failed to serialize {}: {:?}", key, error);
+        },
+    }
+}
+
+pub(crate) fn remove(
+    kind: StorageKind,
+    key: &str,
+) {
+    if let Some(storage) = kind.storage() {
+        let _ = storage.remove_item(key);
+    }
+}
+
+/// Loads and removes the value at once.
+pub(crate) fn take<T: DeserializeOwned>(
+    kind: StorageKind,
+    key: &str,
+) -> Option<T> {
+    let value = load(kind, key);
+    remove(kind, key);
+    value
+}
+
+/// The key of the value that belongs to the account.
+pub(crate) fn account_key(
+    key: &str,
+    local_id: &str,
+) -> String {
+    format!("{}:{}", key, local_id)
+}
+
+/// Removes all persisted data of the account.
+pub(crate) fn clear_account_data(local_id: &str) {
+    for (kind, key) in ACCOUNT_KEYS {
+        remove(*kind, key);
+    }
+    for (kind, key) in PER_ACCOUNT_KEYS {
+        remove(*kind, &account_key(key, local_id));
+    }
+}
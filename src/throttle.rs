@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error_presentation::{ErrorMessage, ErrorPresentation},
+    storage::{self, StorageKind, SIGN_IN_ATTEMPTS_KEY},
+};
+
+// NOTE: Failures allowed before the cooldown starts
+const FREE_ATTEMPTS: u32 = 3;
+const BASE_COOLDOWN_SECONDS: f64 = 5.0;
+const MAX_COOLDOWN_SECONDS: f64 = 300.0;
+// NOTE: Firebase does not tell how long its own lockout lasts
+const SERVER_LOCKOUT_SECONDS: f64 = 60.0;
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+struct AttemptRecord {
+    failures: u32,
+    // NOTE: Milliseconds since the UNIX epoch
+    locked_until: f64,
+}
+
+type AttemptRecords = HashMap<String, AttemptRecord>;
+
+/// Remaining seconds until signing in with the e-mail is allowed again.
+pub(crate) fn remaining_cooldown(email: &str) -> u64 {
+    load_records()
+        .get(&normalize(email))
+        .map(|record| seconds_until(record.locked_until))
+        .unwrap_or(0)
+}
+
+/// Records the failed sign-in and returns the cooldown in seconds.
+pub(crate) fn record_failure(
+    email: &str,
+    error: &ErrorPresentation,
+) -> u64 {
+    let mut records = load_records();
+    let record = records
+        .entry(normalize(email))
+        .or_default();
+
+    match error.message {
+        | ErrorMessage::TooManyAttempts => {
+            record.locked_until = record
+                .locked_until
+                .max(now() + SERVER_LOCKOUT_SECONDS * 1000.0);
+        },
+        // NOTE: Network errors are not the user's attempts
+        | ErrorMessage::Network | ErrorMessage::Unexpected => {},
+        | _ => {
+            record.failures += 1;
+            if record.failures > FREE_ATTEMPTS {
+                let exponent = (record.failures - FREE_ATTEMPTS - 1).min(16);
+                let cooldown = (BASE_COOLDOWN_SECONDS
+                    * 2_f64.powi(exponent as i32))
+                .min(MAX_COOLDOWN_SECONDS);
+                record.locked_until = now() + cooldown * 1000.0;
+            }
+        },
+    }
+
+    let remaining = seconds_until(record.locked_until);
+    save_records(&records);
+    remaining
+}
+
+/// Forgets the failures of the e-mail.
+pub(crate) fn record_success(email: &str) {
+    let mut records = load_records();
+    if records
+        .remove(&normalize(email))
+        .is_some()
+    {
+        save_records(&records);
+    }
+}
+
+fn normalize(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+fn now() -> f64 {
+    js_sys::Date::now()
+}
+
+fn seconds_until(time: f64) -> u64 {
+    ((time - now()) / 1000.0)
+        .ceil()
+        .max(0.0) as u64
+}
+
+fn load_records() -> AttemptRecords {
+    storage::load(StorageKind::Session, SIGN_IN_ATTEMPTS_KEY)
+        .unwrap_or_default()
+}
+
+fn save_records(records: &AttemptRecords) {
+    storage::save(
+        StorageKind::Session,
+        SIGN_IN_ATTEMPTS_KEY,
+        records,
+    );
+}